@@ -4,6 +4,16 @@ mod m20220101_000001_create_attendance_table;
 mod m20220102_000002_seed_attendance;
 mod m20220103_000003_create_banner_table;
 mod m20220104_000004_seed_banner;
+mod m20220105_000005_add_banner_image_variants;
+mod m20220106_000006_create_media_table;
+mod m20220107_000007_add_banner_deleted_at;
+mod m20220108_000008_create_banner_revision_table;
+mod m20220109_000009_add_banner_status;
+mod m20220110_000010_add_banner_recurrence;
+mod m20220111_000011_create_banner_translation_table;
+mod m20220112_000012_add_banner_rich_content;
+mod m20220113_000013_add_attendance_department;
+mod m20220114_000014_create_webhook_tables;
 
 
 pub struct Migrator;
@@ -16,6 +26,16 @@ impl MigratorTrait for Migrator {
             Box::new(m20220102_000002_seed_attendance::Migration),
             Box::new(m20220103_000003_create_banner_table::Migration),
             Box::new(m20220104_000004_seed_banner::Migration),
+            Box::new(m20220105_000005_add_banner_image_variants::Migration),
+            Box::new(m20220106_000006_create_media_table::Migration),
+            Box::new(m20220107_000007_add_banner_deleted_at::Migration),
+            Box::new(m20220108_000008_create_banner_revision_table::Migration),
+            Box::new(m20220109_000009_add_banner_status::Migration),
+            Box::new(m20220110_000010_add_banner_recurrence::Migration),
+            Box::new(m20220111_000011_create_banner_translation_table::Migration),
+            Box::new(m20220112_000012_add_banner_rich_content::Migration),
+            Box::new(m20220113_000013_add_attendance_department::Migration),
+            Box::new(m20220114_000014_create_webhook_tables::Migration),
         ]
     }
 }
@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Tambah kolom URL untuk varian gambar (thumbnail, medium, webp)
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Banner::Table)
+                    .add_column(ColumnDef::new(Banner::ImageThumbnailUrl).string_len(500).null())
+                    .add_column(ColumnDef::new(Banner::ImageMediumUrl).string_len(500).null())
+                    .add_column(ColumnDef::new(Banner::ImageWebpUrl).string_len(500).null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Banner::Table)
+                    .drop_column(Banner::ImageThumbnailUrl)
+                    .drop_column(Banner::ImageMediumUrl)
+                    .drop_column(Banner::ImageWebpUrl)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Banner {
+    Table,
+    ImageThumbnailUrl,
+    ImageMediumUrl,
+    ImageWebpUrl,
+}
@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Create media table (tracking file upload + jumlah referensi)
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Media::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Media::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Media::StorageKey).string_len(500).not_null().unique_key())
+                    .col(ColumnDef::new(Media::SizeBytes).big_integer().not_null().default(0))
+                    .col(ColumnDef::new(Media::RefCount).integer().not_null().default(0))
+                    .col(ColumnDef::new(Media::UnreferencedSince).date_time().null())
+                    .col(ColumnDef::new(Media::CreatedAt).date_time().null())
+                    .col(ColumnDef::new(Media::UpdatedAt).date_time().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_media_ref_count_unreferenced_since")
+                    .table(Media::Table)
+                    .col(Media::RefCount)
+                    .col(Media::UnreferencedSince)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    // Drop media table
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Media::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Media {
+    Table,
+    Id,
+    StorageKey,
+    SizeBytes,
+    RefCount,
+    UnreferencedSince,
+    CreatedAt,
+    UpdatedAt,
+}
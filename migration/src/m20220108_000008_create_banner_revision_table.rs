@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Create banner_revision table (riwayat perubahan banner, snapshot penuh per revisi)
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BannerRevision::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BannerRevision::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(BannerRevision::BannerId).integer().not_null())
+                    .col(ColumnDef::new(BannerRevision::Revision).integer().not_null())
+                    .col(ColumnDef::new(BannerRevision::Action).string_len(50).not_null())
+                    .col(ColumnDef::new(BannerRevision::ChangedBy).string_len(100).null())
+                    .col(ColumnDef::new(BannerRevision::Snapshot).json().not_null())
+                    .col(ColumnDef::new(BannerRevision::CreatedAt).date_time().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_banner_revision_banner_id_revision")
+                    .table(BannerRevision::Table)
+                    .col(BannerRevision::BannerId)
+                    .col(BannerRevision::Revision)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    // Drop banner_revision table
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BannerRevision::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum BannerRevision {
+    Table,
+    Id,
+    BannerId,
+    Revision,
+    Action,
+    ChangedBy,
+    Snapshot,
+    CreatedAt,
+}
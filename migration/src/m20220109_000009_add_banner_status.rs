@@ -0,0 +1,99 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Workflow banner: draft -> pending_review -> approved -> published -> archived
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Banner::Table)
+                    .add_column(
+                        ColumnDef::new(Banner::Status)
+                            .string_len(20)
+                            .not_null()
+                            .default("draft"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Banner lama yang aktif dianggap sudah published supaya tetap tampil
+        manager
+            .get_connection()
+            .execute(Statement::from_string(
+                manager.get_database_backend(),
+                "UPDATE banner SET status = 'published' WHERE is_active = 1",
+            ))
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(BannerReview::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BannerReview::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(BannerReview::BannerId).integer().not_null())
+                    .col(ColumnDef::new(BannerReview::FromStatus).string_len(20).not_null())
+                    .col(ColumnDef::new(BannerReview::ToStatus).string_len(20).not_null())
+                    .col(ColumnDef::new(BannerReview::Actor).string_len(100).not_null())
+                    .col(ColumnDef::new(BannerReview::Comment).text().null())
+                    .col(ColumnDef::new(BannerReview::CreatedAt).date_time().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_banner_review_banner_id")
+                    .table(BannerReview::Table)
+                    .col(BannerReview::BannerId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BannerReview::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Banner::Table)
+                    .drop_column(Banner::Status)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Banner {
+    Table,
+    Status,
+}
+
+#[derive(Iden)]
+enum BannerReview {
+    Table,
+    Id,
+    BannerId,
+    FromStatus,
+    ToStatus,
+    Actor,
+    Comment,
+    CreatedAt,
+}
@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Jadwal berulang (RRULE) + durasi tiap jendela tampil
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Banner::Table)
+                    .add_column(ColumnDef::new(Banner::RecurrenceRule).string_len(500).null())
+                    .add_column(ColumnDef::new(Banner::RecurrenceDurationMinutes).integer().null())
+                    .add_column(ColumnDef::new(Banner::RecurrenceTimezone).string_len(64).null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Banner::Table)
+                    .drop_column(Banner::RecurrenceRule)
+                    .drop_column(Banner::RecurrenceDurationMinutes)
+                    .drop_column(Banner::RecurrenceTimezone)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Banner {
+    Table,
+    RecurrenceRule,
+    RecurrenceDurationMinutes,
+    RecurrenceTimezone,
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Create banner_translation table (judul & konten per locale)
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BannerTranslation::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BannerTranslation::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(BannerTranslation::BannerId).integer().not_null())
+                    .col(ColumnDef::new(BannerTranslation::Locale).string_len(16).not_null())
+                    .col(ColumnDef::new(BannerTranslation::Title).string_len(255).null())
+                    .col(ColumnDef::new(BannerTranslation::Content).text().not_null())
+                    .col(ColumnDef::new(BannerTranslation::CreatedAt).date_time().null())
+                    .col(ColumnDef::new(BannerTranslation::UpdatedAt).date_time().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_banner_translation_banner_id_locale")
+                    .table(BannerTranslation::Table)
+                    .col(BannerTranslation::BannerId)
+                    .col(BannerTranslation::Locale)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    // Drop banner_translation table
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BannerTranslation::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum BannerTranslation {
+    Table,
+    Id,
+    BannerId,
+    Locale,
+    Title,
+    Content,
+    CreatedAt,
+    UpdatedAt,
+}
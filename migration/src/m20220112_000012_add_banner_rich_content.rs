@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Konten Markdown: simpan juga HTML hasil render + tombol CTA opsional
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Banner::Table)
+                    .add_column(ColumnDef::new(Banner::ContentHtml).text().null())
+                    .add_column(ColumnDef::new(Banner::CtaLabel).string_len(100).null())
+                    .add_column(ColumnDef::new(Banner::CtaUrl).string_len(500).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BannerTranslation::Table)
+                    .add_column(ColumnDef::new(BannerTranslation::ContentHtml).text().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BannerTranslation::Table)
+                    .drop_column(BannerTranslation::ContentHtml)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Banner::Table)
+                    .drop_column(Banner::ContentHtml)
+                    .drop_column(Banner::CtaLabel)
+                    .drop_column(Banner::CtaUrl)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Banner {
+    Table,
+    ContentHtml,
+    CtaLabel,
+    CtaUrl,
+}
+
+#[derive(Iden)]
+enum BannerTranslation {
+    Table,
+    ContentHtml,
+}
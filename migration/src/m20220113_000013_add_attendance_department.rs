@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Departemen dicatat per sesi clock-in, untuk filter papan presence
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Attendance::Table)
+                    .add_column(ColumnDef::new(Attendance::Department).string_len(100).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_attendance_open_sessions")
+                    .table(Attendance::Table)
+                    .col(Attendance::ClockOutTime)
+                    .col(Attendance::Department)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_attendance_open_sessions")
+                    .table(Attendance::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Attendance::Table)
+                    .drop_column(Attendance::Department)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Attendance {
+    Table,
+    ClockOutTime,
+    Department,
+}
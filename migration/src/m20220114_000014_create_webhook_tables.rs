@@ -0,0 +1,169 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Webhook keluar: langganan + antrean pengiriman (outbox) + penanda banner expired
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookSubscription::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WebhookSubscription::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WebhookSubscription::Url).string_len(500).not_null())
+                    .col(ColumnDef::new(WebhookSubscription::Secret).string_len(100).not_null())
+                    .col(ColumnDef::new(WebhookSubscription::Events).string_len(255).not_null())
+                    .col(
+                        ColumnDef::new(WebhookSubscription::IsActive)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(ColumnDef::new(WebhookSubscription::CreatedBy).string_len(100).null())
+                    .col(ColumnDef::new(WebhookSubscription::CreatedAt).date_time().null())
+                    .col(ColumnDef::new(WebhookSubscription::UpdatedAt).date_time().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookDelivery::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::SubscriptionId).integer().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::EventId).string_len(36).not_null())
+                    .col(ColumnDef::new(WebhookDelivery::Event).string_len(100).not_null())
+                    .col(ColumnDef::new(WebhookDelivery::Payload).text().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::Status).string_len(20).not_null())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::NextAttemptAt).date_time().null())
+                    .col(ColumnDef::new(WebhookDelivery::LastStatusCode).integer().null())
+                    .col(ColumnDef::new(WebhookDelivery::LastError).text().null())
+                    .col(ColumnDef::new(WebhookDelivery::DeliveredAt).date_time().null())
+                    .col(ColumnDef::new(WebhookDelivery::CreatedAt).date_time().null())
+                    .col(ColumnDef::new(WebhookDelivery::UpdatedAt).date_time().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webhook_delivery_due")
+                    .table(WebhookDelivery::Table)
+                    .col(WebhookDelivery::Status)
+                    .col(WebhookDelivery::NextAttemptAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webhook_delivery_subscription_id")
+                    .table(WebhookDelivery::Table)
+                    .col(WebhookDelivery::SubscriptionId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Banner::Table)
+                    .add_column(ColumnDef::new(Banner::ExpiryNotifiedAt).date_time().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Banner yang sudah lewat end_date tidak perlu dikirim lagi sebagai banner.expired
+        manager
+            .get_connection()
+            .execute(Statement::from_string(
+                manager.get_database_backend(),
+                "UPDATE banner SET expiry_notified_at = end_date WHERE end_date <= NOW()",
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Banner::Table)
+                    .drop_column(Banner::ExpiryNotifiedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(WebhookDelivery::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(WebhookSubscription::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum WebhookSubscription {
+    Table,
+    Id,
+    Url,
+    Secret,
+    Events,
+    IsActive,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum WebhookDelivery {
+    Table,
+    Id,
+    SubscriptionId,
+    EventId,
+    Event,
+    Payload,
+    Status,
+    Attempts,
+    NextAttemptAt,
+    LastStatusCode,
+    LastError,
+    DeliveredAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Banner {
+    Table,
+    ExpiryNotifiedAt,
+}
@@ -0,0 +1,154 @@
+use actix_web::dev::Payload;
+use actix_web::http::{StatusCode, header};
+use actix_web::{FromRequest, HttpRequest, HttpResponse, ResponseError, web};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::{Ready, ready};
+
+use crate::responses::ApiResponse;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Editor,
+    Reviewer,
+    Admin,
+}
+
+impl Role {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "editor" => Some(Role::Editor),
+            "reviewer" => Some(Role::Reviewer),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// User yang melakukan request, diambil dari header `Authorization: Bearer <token>`.
+#[derive(Clone, Debug)]
+pub struct Actor {
+    pub user_id: String,
+    pub role: Role,
+    pub department: Option<String>, // batas papan presence untuk non-admin
+}
+
+impl Actor {
+    // Reviewer dan admin boleh meng-approve / publish banner
+    pub fn can_review(&self) -> bool {
+        matches!(self.role, Role::Reviewer | Role::Admin)
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.role == Role::Admin
+    }
+}
+
+// Token API statis dari env, format: API_TOKENS=token:user_id[:role[:department]],... (role default editor)
+#[derive(Clone, Default)]
+pub struct ApiTokens(HashMap<String, Actor>);
+
+impl ApiTokens {
+    pub fn parse(spec: &str) -> Self {
+        let tokens = spec
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.trim().splitn(4, ':');
+                let token = parts.next().filter(|t| !t.is_empty())?;
+                let user_id = parts.next().filter(|u| !u.is_empty())?;
+                let role = match parts.next() {
+                    Some(r) => Role::parse(r)?,
+                    None => Role::Editor,
+                };
+                let department = parts.next().map(str::trim).filter(|d| !d.is_empty());
+                Some((
+                    token.to_string(),
+                    Actor {
+                        user_id: user_id.to_string(),
+                        role,
+                        department: department.map(str::to_string),
+                    },
+                ))
+            })
+            .collect();
+        Self(tokens)
+    }
+
+    pub fn lookup(&self, token: &str) -> Option<&Actor> {
+        self.0.get(token)
+    }
+}
+
+#[derive(Debug)]
+pub struct AuthError;
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Missing or invalid API token")
+    }
+}
+
+impl ResponseError for AuthError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::UNAUTHORIZED
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::Unauthorized().json(ApiResponse::<()>::error(&self.to_string()))
+    }
+}
+
+impl FromRequest for Actor {
+    type Error = AuthError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let token = bearer_token(req);
+        let actor = match (token, req.app_data::<web::Data<ApiTokens>>()) {
+            (Some(token), Some(tokens)) => tokens.lookup(token).cloned(),
+            _ => None,
+        };
+        ready(actor.ok_or(AuthError))
+    }
+}
+
+// Token dari header `Authorization: Bearer <token>`, belum dicek ke ApiTokens
+pub fn bearer_token(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// Returned by handlers when the caller is authenticated but lacks the role.
+pub fn forbidden(message: &str) -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiResponse::<()>::error(message))
+}
+
+/// Browser WebSocket clients cannot set `Authorization`, so the same token may
+/// be passed as `?access_token=` on endpoints that opt in.
+pub fn actor_from_query(req: &HttpRequest, query: &HashMap<String, String>) -> Option<Actor> {
+    let token = query.get("access_token")?;
+    req.app_data::<web::Data<ApiTokens>>()?
+        .lookup(token.trim())
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_role_and_department() {
+        let tokens = ApiTokens::parse("t1:alice, t2:bob:reviewer:Sales ,t3:root:admin,t4:eve:owner,:nobody");
+        let alice = tokens.lookup("t1").unwrap();
+        assert_eq!((alice.role, alice.department.as_deref()), (Role::Editor, None));
+        let bob = tokens.lookup("t2").unwrap();
+        assert_eq!((bob.role, bob.department.as_deref()), (Role::Reviewer, Some("Sales")));
+        assert!(tokens.lookup("t3").unwrap().is_admin());
+        // role tidak dikenal atau token kosong diabaikan
+        assert!(tokens.lookup("t4").is_none());
+        assert!(tokens.lookup("").is_none());
+    }
+}
@@ -0,0 +1,175 @@
+use actix_web::HttpRequest;
+use actix_web::http::header::{self, HttpDate};
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::Notify;
+
+use crate::config::HttpCacheConfig;
+use crate::entity::{banner, banner_translation};
+use crate::workflow::BannerStatus;
+
+// File upload disimpan dengan key unik per upload, jadi isinya tidak pernah berubah
+pub const UPLOADS_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// Banner yang berpeluang tampil di `/api/banners/active`, beserta semua terjemahannya.
+///
+/// Batas tanggal dan jadwal berulang tetap dicek per request, jadi snapshot ini
+/// tidak basi hanya karena waktu berjalan.
+pub struct ActiveBanners {
+    pub candidates: Vec<banner::Model>,
+    pub translations: Vec<banner_translation::Model>,
+}
+
+impl ActiveBanners {
+    pub async fn load<C: ConnectionTrait>(db: &C) -> Result<Self, DbErr> {
+        let now = Utc::now().naive_utc();
+        let candidates = banner::Entity::find()
+            .filter(banner::Column::IsActive.eq(true))
+            .filter(banner::Column::Status.eq(BannerStatus::Published.as_str()))
+            .filter(banner::Column::DeletedAt.is_null())
+            .filter(banner::Column::EndDate.gt(now))
+            .order_by_asc(banner::Column::EndDate) // Prioritas banner yang akan expired lebih dulu
+            .order_by_desc(banner::Column::CreatedAt) // Jika end_date sama, pilih yang terbaru dibuat
+            .all(db)
+            .await?;
+
+        let ids: Vec<i32> = candidates.iter().map(|b| b.id).collect();
+        let translations = if ids.is_empty() {
+            Vec::new()
+        } else {
+            banner_translation::Entity::find()
+                .filter(banner_translation::Column::BannerId.is_in(ids))
+                .all(db)
+                .await?
+        };
+
+        Ok(Self {
+            candidates,
+            translations,
+        })
+    }
+}
+
+struct Entry {
+    banners: Arc<ActiveBanners>,
+    loaded_at: Instant,
+}
+
+/// In-process cache for the active banner lookup.
+///
+/// Every successful write under `/api/banners` calls [`invalidate`](Self::invalidate).
+/// The TTL only guards against edits made outside the API (seeders, manual SQL).
+pub struct ActiveBannerCache {
+    entry: RwLock<Option<Entry>>,
+    generation: AtomicU64,
+    changed: Notify,
+    ttl: Duration,
+    pub max_age_secs: u64,
+}
+
+impl ActiveBannerCache {
+    pub fn new(cfg: &HttpCacheConfig) -> Self {
+        Self {
+            entry: RwLock::new(None),
+            generation: AtomicU64::new(0),
+            changed: Notify::new(),
+            ttl: Duration::from_secs(cfg.active_ttl_secs),
+            max_age_secs: cfg.active_max_age_secs,
+        }
+    }
+
+    pub async fn get_or_load<C: ConnectionTrait>(&self, db: &C) -> Result<Arc<ActiveBanners>, DbErr> {
+        if let Some(entry) = self.entry.read().unwrap().as_ref()
+            && entry.loaded_at.elapsed() < self.ttl
+        {
+            return Ok(entry.banners.clone());
+        }
+
+        // Hasil query yang berjalan bersamaan dengan invalidate() tidak disimpan
+        let generation = self.generation.load(Ordering::SeqCst);
+        let banners = Arc::new(ActiveBanners::load(db).await?);
+        let mut entry = self.entry.write().unwrap();
+        if self.generation.load(Ordering::SeqCst) == generation {
+            *entry = Some(Entry {
+                banners: banners.clone(),
+                loaded_at: Instant::now(),
+            });
+        }
+        Ok(banners)
+    }
+
+    pub fn invalidate(&self) {
+        let mut entry = self.entry.write().unwrap();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        *entry = None;
+        self.changed.notify_one();
+    }
+
+    // Selesai saat invalidate() dipanggil (dipakai watcher stream banner)
+    pub async fn invalidated(&self) {
+        self.changed.notified().await;
+    }
+}
+
+/// Strong ETag for a banner as served in one locale.
+pub fn etag(b: &banner::Model, translation: Option<&banner_translation::Model>) -> String {
+    let mut tag = format!("b{}-{}", b.id, millis(b.updated_at));
+    if let Some(t) = translation {
+        tag.push_str(&format!("-{}-{}", t.locale, millis(t.updated_at)));
+    }
+    format!("\"{}\"", tag)
+}
+
+/// Jendela validasi untuk respons berisi URL gambar yang punya masa berlaku (`ttl_secs`).
+///
+/// Setengah TTL: salinan yang divalidasi ulang di jendela yang sama masih memegang URL
+/// yang berlaku paling sedikit setengah TTL lagi. Dipakai juga sebagai batas max-age.
+pub fn signed_url_window(ttl_secs: u32) -> u64 {
+    (ttl_secs as u64 / 2).max(1)
+}
+
+// ETag yang berganti setiap jendela, supaya 304 tidak mempertahankan URL yang sudah kedaluwarsa
+pub fn etag_for_window(etag: &str, window_secs: u64) -> String {
+    let epoch = Utc::now().timestamp().max(0) as u64 / window_secs;
+    format!("{}-u{}\"", etag.trim_end_matches('"'), epoch)
+}
+
+fn millis(ts: Option<NaiveDateTime>) -> i64 {
+    ts.map(|t| t.and_utc().timestamp_millis()).unwrap_or(0)
+}
+
+pub fn http_date(ts: NaiveDateTime) -> HttpDate {
+    let secs = ts.and_utc().timestamp().max(0) as u64;
+    HttpDate::from(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+/// Apakah salinan di client masih sama, berdasarkan `If-None-Match` atau,
+/// kalau header itu tidak ada, `If-Modified-Since`.
+pub fn is_fresh(req: &HttpRequest, etag: &str, last_modified: Option<NaiveDateTime>) -> bool {
+    let headers = req.headers();
+    if let Some(value) = headers.get(header::IF_NONE_MATCH) {
+        // If-None-Match memakai perbandingan lemah (prefix W/ diabaikan)
+        let etag = etag.trim_start_matches("W/");
+        return value.to_str().is_ok_and(|v| {
+            v.split(',')
+                .map(|t| t.trim())
+                .any(|t| t == "*" || t.trim_start_matches("W/") == etag)
+        });
+    }
+
+    match (
+        last_modified,
+        headers
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<HttpDate>().ok()),
+    ) {
+        (Some(modified), Some(since)) => {
+            SystemTime::from(http_date(modified)) <= SystemTime::from(since)
+        }
+        _ => false,
+    }
+}
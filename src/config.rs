@@ -1,12 +1,750 @@
-use sea_orm::Database;
-use sea_orm::DatabaseConnection;
+use ::config::{Config, Environment, File};
+use actix_cors::Cors;
+use actix_web::http::Method;
+use actix_web::http::header::HeaderName;
+use sea_orm::{ConnectOptions, Database, DatabaseConnection};
 use dotenvy::dotenv;
+use serde::Deserialize;
 use std::env;
+use std::sync::Arc;
+use tracing_subscriber::EnvFilter;
 
-pub async fn init_db() -> DatabaseConnection {
-    dotenv().ok();
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set in .env");
-    Database::connect(&database_url)
+use crate::storage::{BlobStore, LocalStore, MediaAccess, S3Options, S3Store};
+use crate::telemetry;
+
+/// Seluruh konfigurasi aplikasi, dimuat sekali saat startup.
+///
+/// Urutan sumber (yang belakang menimpa yang depan):
+/// 1. nilai default di struct ini
+/// 2. `config/default.toml`
+/// 3. `config/{APP_ENV}.toml` (APP_ENV default: development)
+/// 4. env lama yang sudah dipakai sebelumnya, mis. `APP_PORT`, `DATABASE_URL`
+/// 5. env `APP__<SECTION>__<KEY>`, mis. `APP__SERVER__PORT=9000`
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub server: ServerConfig,
+    pub log: LogConfig,
+    pub database: DatabaseConfig,
+    pub storage: StorageConfig,
+    pub uploads: UploadConfig,
+    pub auth: AuthConfig,
+    pub i18n: I18nConfig,
+    pub cors: CorsConfig,
+    pub rate_limit: RateLimitConfig,
+    pub media_gc: MediaGcConfig,
+    pub trash_purge: TrashPurgeConfig,
+    pub http_cache: HttpCacheConfig,
+    pub banner_stream: BannerStreamConfig,
+    pub webhooks: WebhookConfig,
+    pub shutdown: ShutdownConfig,
+}
+
+// Env lama -> key konfigurasi, supaya .env dan docker-compose yang ada tetap jalan
+const LEGACY_ENV: &[(&str, &str)] = &[
+    ("APP_HOST", "server.host"),
+    ("APP_PORT", "server.port"),
+    ("LOG_LEVEL", "log.level"),
+    ("LOG_FORMAT", "log.format"),
+    ("DATABASE_URL", "database.url"),
+    ("STORAGE_BACKEND", "storage.backend"),
+    ("UPLOAD_DIR", "storage.upload_dir"),
+    ("UPLOAD_PRIVATE_PREFIXES", "storage.private_prefixes"),
+    ("UPLOAD_SIGNING_SECRET", "storage.signing_secret"),
+    ("UPLOAD_SIGNED_URL_TTL_SECS", "storage.signed_url_ttl_secs"),
+    ("UPLOAD_MAX_FILE_BYTES", "uploads.max_file_bytes"),
+    ("UPLOAD_MAX_REQUEST_BYTES", "uploads.max_request_bytes"),
+    ("UPLOAD_MAX_IMAGE_DIMENSION", "uploads.max_image_dimension"),
+    ("S3_BUCKET", "storage.s3.bucket"),
+    ("S3_REGION", "storage.s3.region"),
+    ("S3_ENDPOINT", "storage.s3.endpoint"),
+    ("S3_ACCESS_KEY", "storage.s3.access_key"),
+    ("S3_SECRET_KEY", "storage.s3.secret_key"),
+    ("S3_PATH_STYLE", "storage.s3.path_style"),
+    ("S3_PUBLIC_URL", "storage.s3.public_url"),
+    ("S3_PRESIGN_TTL_SECS", "storage.s3.presign_ttl_secs"),
+    ("API_TOKENS", "auth.api_tokens"),
+    ("DEFAULT_LOCALE", "i18n.default_locale"),
+    ("CORS_ALLOWED_ORIGINS", "cors.allowed_origins"),
+    ("CORS_ALLOWED_METHODS", "cors.allowed_methods"),
+    ("CORS_ALLOWED_HEADERS", "cors.allowed_headers"),
+    ("CORS_EXPOSE_HEADERS", "cors.expose_headers"),
+    ("CORS_MAX_AGE_SECS", "cors.max_age_secs"),
+    ("CORS_ALLOW_CREDENTIALS", "cors.allow_credentials"),
+    ("RATE_LIMIT_ENABLED", "rate_limit.enabled"),
+    ("RATE_LIMIT_TRUST_PROXY", "rate_limit.trust_proxy"),
+    ("MEDIA_GC_INTERVAL_SECS", "media_gc.interval_secs"),
+    ("MEDIA_GC_GRACE_SECS", "media_gc.grace_secs"),
+    ("BANNER_TRASH_PURGE_INTERVAL_SECS", "trash_purge.interval_secs"),
+    ("BANNER_TRASH_RETENTION_DAYS", "trash_purge.retention_days"),
+    ("ACTIVE_BANNER_CACHE_TTL_SECS", "http_cache.active_ttl_secs"),
+    ("ACTIVE_BANNER_MAX_AGE_SECS", "http_cache.active_max_age_secs"),
+    ("BANNER_STREAM_HEARTBEAT_SECS", "banner_stream.heartbeat_secs"),
+    ("WEBHOOK_POLL_INTERVAL_SECS", "webhooks.poll_interval_secs"),
+    ("WEBHOOK_TIMEOUT_SECS", "webhooks.timeout_secs"),
+    ("WEBHOOK_MAX_ATTEMPTS", "webhooks.max_attempts"),
+    ("WEBHOOK_BACKOFF_BASE_SECS", "webhooks.backoff_base_secs"),
+    ("WEBHOOK_BACKOFF_MAX_SECS", "webhooks.backoff_max_secs"),
+    ("BANNER_EXPIRY_CHECK_INTERVAL_SECS", "webhooks.expiry_check_secs"),
+    ("SHUTDOWN_TIMEOUT_SECS", "shutdown.timeout_secs"),
+    ("SHUTDOWN_READINESS_DELAY_SECS", "shutdown.readiness_delay_secs"),
+];
+
+// Key yang nilainya daftar dipisah koma di env, mis. CORS_ALLOWED_ORIGINS=https://a.com,https://b.com
+const LIST_KEYS: &[&str] = &[
+    "storage.private_prefixes",
+    "cors.allowed_origins",
+    "cors.allowed_methods",
+    "cors.allowed_headers",
+    "cors.expose_headers",
+];
+
+// Sumber env dengan parsing angka, bool dan daftar
+fn env_source(env: Environment) -> Environment {
+    LIST_KEYS
+        .iter()
+        .fold(env.try_parsing(true).list_separator(","), |env, key| env.with_list_parse_key(key))
+}
+
+impl Settings {
+    /// Muat dan validasi konfigurasi. Error berisi satu masalah per baris.
+    pub fn load() -> Result<Self, String> {
+        dotenv().ok();
+        let dir = env::var("CONFIG_DIR").unwrap_or_else(|_| "config".to_string());
+        let app_env = env::var("APP_ENV").unwrap_or_else(|_| "development".to_string());
+
+        // Env lama dipetakan ke key-nya; APP__* ditambahkan sesudahnya jadi menang
+        let legacy = LEGACY_ENV
+            .iter()
+            .filter_map(|(var, key)| Some((key.replace('.', "__"), env::var(var).ok()?)))
+            .collect();
+
+        let builder = Config::builder()
+            .add_source(File::with_name(&format!("{}/default", dir)).required(false))
+            .add_source(File::with_name(&format!("{}/{}", dir, app_env)).required(false))
+            .add_source(env_source(Environment::default().source(Some(legacy)).separator("__")))
+            .add_source(env_source(
+                Environment::with_prefix("APP").prefix_separator("__").separator("__"),
+            ));
+
+        let settings: Settings = builder
+            .build()
+            .and_then(|c| c.try_deserialize())
+            .map_err(|e| e.to_string())?;
+        settings.validate().map_err(|errors| errors.join("\n"))?;
+        Ok(settings)
+    }
+
+    fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        let mut check = |ok: bool, message: &str| {
+            if !ok {
+                errors.push(message.to_string());
+            }
+        };
+
+        check(self.server.port != 0, "server.port (APP_PORT) must be between 1 and 65535");
+        check(
+            EnvFilter::try_new(&self.log.level).is_ok(),
+            "log.level (LOG_LEVEL) must be a tracing filter, e.g. info or info,sqlx=warn",
+        );
+        check(
+            matches!(self.log.format.as_str(), "text" | "json"),
+            "log.format (LOG_FORMAT) must be text or json",
+        );
+        check(
+            self.database.url.as_deref().is_some_and(|u| !u.trim().is_empty()),
+            "database.url (DATABASE_URL) must be set",
+        );
+        match self.storage.backend.as_str() {
+            "local" => check(
+                !self.storage.upload_dir.trim().is_empty(),
+                "storage.upload_dir (UPLOAD_DIR) must not be empty",
+            ),
+            "s3" => check(
+                self.storage.s3.bucket.as_deref().is_some_and(|b| !b.is_empty()),
+                "storage.s3.bucket (S3_BUCKET) must be set when storage.backend is s3",
+            ),
+            _ => check(false, "storage.backend (STORAGE_BACKEND) must be local or s3"),
+        }
+        if !self.storage.private_prefixes.is_empty() {
+            check(
+                self.storage.private_prefixes.iter().all(|p| !p.trim().is_empty()),
+                "storage.private_prefixes (UPLOAD_PRIVATE_PREFIXES) must not contain empty prefixes",
+            );
+            check(
+                self.storage.signed_url_ttl_secs > 0,
+                "storage.signed_url_ttl_secs (UPLOAD_SIGNED_URL_TTL_SECS) must be greater than 0",
+            );
+            // S3 menandatangani dengan kredensialnya sendiri
+            check(
+                self.storage.backend != "local"
+                    || self.storage.signing_secret.as_deref().is_some_and(|s| s.len() >= 32),
+                "storage.signing_secret (UPLOAD_SIGNING_SECRET) must be at least 32 characters when private_prefixes is set",
+            );
+        }
+        check(
+            self.uploads.max_file_bytes > 0 && self.uploads.max_file_bytes <= self.uploads.max_request_bytes,
+            "uploads.max_file_bytes (UPLOAD_MAX_FILE_BYTES) must be > 0 and not above uploads.max_request_bytes",
+        );
+        check(
+            self.uploads.max_image_dimension > 0,
+            "uploads.max_image_dimension (UPLOAD_MAX_IMAGE_DIMENSION) must be greater than 0",
+        );
+        check(
+            !self.i18n.default_locale.trim().is_empty(),
+            "i18n.default_locale (DEFAULT_LOCALE) must not be empty",
+        );
+        for e in self.cors.problems() {
+            check(false, &e);
+        }
+        for (name, policy) in self.rate_limit.policies() {
+            check(
+                policy.burst > 0 && policy.per_minute > 0,
+                &format!("rate_limit.{}: burst and per_minute must be greater than 0", name),
+            );
+        }
+        check(self.media_gc.interval_secs > 0, "media_gc.interval_secs must be greater than 0");
+        check(self.media_gc.grace_secs >= 0, "media_gc.grace_secs must not be negative");
+        check(self.trash_purge.interval_secs > 0, "trash_purge.interval_secs must be greater than 0");
+        check(self.trash_purge.retention_days > 0, "trash_purge.retention_days must be greater than 0");
+        check(self.http_cache.active_ttl_secs > 0, "http_cache.active_ttl_secs must be greater than 0");
+        check(
+            self.banner_stream.heartbeat_secs > 0,
+            "banner_stream.heartbeat_secs must be greater than 0",
+        );
+        check(self.webhooks.poll_interval_secs > 0, "webhooks.poll_interval_secs must be greater than 0");
+        check(self.webhooks.timeout_secs > 0, "webhooks.timeout_secs must be greater than 0");
+        check(self.webhooks.max_attempts > 0, "webhooks.max_attempts must be greater than 0");
+        check(
+            self.webhooks.backoff_base_secs > 0
+                && self.webhooks.backoff_max_secs >= self.webhooks.backoff_base_secs,
+            "webhooks.backoff_base_secs must be > 0 and not above webhooks.backoff_max_secs",
+        );
+        check(self.webhooks.expiry_check_secs > 0, "webhooks.expiry_check_secs must be greater than 0");
+        check(
+            self.shutdown.timeout_secs > 0,
+            "shutdown.timeout_secs (SHUTDOWN_TIMEOUT_SECS) must be greater than 0",
+        );
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    pub fn bind_address(&self) -> String {
+        format!("{}:{}", self.server.host, self.server.port)
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: "0.0.0.0".to_string(),
+            port: 8080,
+        }
+    }
+}
+
+// format: text (default) atau json untuk agregator log
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    pub level: String,
+    pub format: String,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            format: "text".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DatabaseConfig {
+    pub url: Option<String>,
+}
+
+// backend: local (default) atau s3
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub backend: String,
+    pub upload_dir: String, // direktori upload lokal, disajikan di /uploads
+    pub private_prefixes: Vec<String>, // key dengan prefix ini hanya lewat URL bertanda tangan
+    pub signing_secret: Option<String>,
+    pub signed_url_ttl_secs: u32,
+    pub s3: S3Config,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: "local".to_string(),
+            upload_dir: "./uploads".to_string(),
+            private_prefixes: Vec::new(),
+            signing_secret: None,
+            signed_url_ttl_secs: 900,
+            s3: S3Config::default(),
+        }
+    }
+}
+
+// Batas upload multipart, dicek selama stream dibaca (bukan setelah semuanya di memori)
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct UploadConfig {
+    pub max_file_bytes: usize,
+    pub max_request_bytes: usize, // seluruh body, termasuk field lain dan header part
+    pub max_image_dimension: u32, // lebar/tinggi maksimum dalam piksel
+}
+
+impl Default for UploadConfig {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: 5 * 1024 * 1024,
+            max_request_bytes: 6 * 1024 * 1024,
+            max_image_dimension: 4096,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct S3Config {
+    pub bucket: Option<String>,
+    pub region: String,
+    pub endpoint: Option<String>,
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+    pub path_style: Option<bool>, // default: true kalau endpoint diisi
+    pub public_url: Option<String>,
+    pub presign_ttl_secs: Option<u32>,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            bucket: None,
+            region: "us-east-1".to_string(),
+            endpoint: None,
+            access_key: None,
+            secret_key: None,
+            path_style: None,
+            public_url: None,
+            presign_ttl_secs: None,
+        }
+    }
+}
+
+// Token API statis, format: token:user_id[:role],...
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    pub api_tokens: String,
+}
+
+// Locale fallback terakhir sebelum konten asli banner (default: id)
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct I18nConfig {
+    pub default_locale: String,
+}
+
+impl Default for I18nConfig {
+    fn default() -> Self {
+        Self {
+            default_locale: "id".to_string(),
+        }
+    }
+}
+
+// Kebijakan CORS; "*" di allowed_origins berarti semua origin (tanpa credentials)
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    pub expose_headers: Vec<String>,
+    pub max_age_secs: usize, // lama browser boleh menyimpan hasil preflight
+    pub allow_credentials: bool,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        let list = |items: &[&str]| items.iter().map(|s| s.to_string()).collect();
+        Self {
+            allowed_origins: list(&["*"]),
+            allowed_methods: list(&["GET", "POST", "PUT", "DELETE"]),
+            allowed_headers: list(&[
+                "Authorization",
+                "Content-Type",
+                "Accept-Language",
+                "If-None-Match",
+                "If-Modified-Since",
+                "Last-Event-ID",
+                "X-Request-Id",
+            ]),
+            expose_headers: list(&[
+                "X-Request-Id",
+                "ETag",
+                "Last-Modified",
+                "Content-Language",
+                "Retry-After",
+                "RateLimit-Limit",
+                "RateLimit-Remaining",
+                "RateLimit-Reset",
+            ]),
+            max_age_secs: 3600,
+            allow_credentials: false,
+        }
+    }
+}
+
+impl CorsConfig {
+    fn any_origin(&self) -> bool {
+        self.allowed_origins.iter().any(|o| o == "*")
+    }
+
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.allowed_origins.is_empty() {
+            problems.push("cors.allowed_origins (CORS_ALLOWED_ORIGINS) must not be empty".to_string());
+        }
+        for origin in self.allowed_origins.iter().filter(|o| *o != "*") {
+            // Origin = scheme://host[:port] tanpa path, sama persis dengan header Origin dari browser
+            let valid = url::Url::parse(origin)
+                .is_ok_and(|u| u.origin().ascii_serialization() == *origin);
+            if !valid {
+                problems.push(format!(
+                    "cors.allowed_origins: {} is not an origin like https://app.example.com",
+                    origin
+                ));
+            }
+        }
+        if self.allow_credentials && self.any_origin() {
+            problems.push("cors.allow_credentials cannot be combined with allowed_origins = *".to_string());
+        }
+        for method in &self.allowed_methods {
+            if Method::from_bytes(method.as_bytes()).is_err() {
+                problems.push(format!("cors.allowed_methods: {} is not an HTTP method", method));
+            }
+        }
+        for header in self.allowed_headers.iter().chain(&self.expose_headers) {
+            if HeaderName::from_bytes(header.as_bytes()).is_err() {
+                problems.push(format!("cors: {} is not a valid header name", header));
+            }
+        }
+        problems
+    }
+}
+
+// Middleware CORS dari konfigurasi yang sudah divalidasi
+pub fn cors(cfg: &CorsConfig) -> Cors {
+    let mut cors = Cors::default()
+        .allowed_methods(cfg.allowed_methods.iter().map(String::as_str))
+        .allowed_headers(cfg.allowed_headers.iter().map(String::as_str))
+        .expose_headers(cfg.expose_headers.iter().map(String::as_str))
+        .max_age(cfg.max_age_secs);
+    if cfg.any_origin() {
+        cors = cors.allow_any_origin().send_wildcard();
+    } else {
+        for origin in &cfg.allowed_origins {
+            cors = cors.allowed_origin(origin);
+        }
+    }
+    if cfg.allow_credentials {
+        cors = cors.supports_credentials();
+    }
+    cors
+}
+
+/// Token bucket: `burst` request sekaligus, lalu terisi lagi `per_minute` token per menit.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct RatePolicy {
+    pub burst: u32,
+    pub per_minute: u32,
+}
+
+impl RatePolicy {
+    pub const fn new(burst: u32, per_minute: u32) -> Self {
+        Self { burst, per_minute }
+    }
+}
+
+// Batas request per user (atau per IP kalau anonim), dipilih menurut route
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub trust_proxy: bool, // pakai X-Forwarded-For / Forwarded untuk IP client; aktifkan hanya di belakang proxy
+    pub default: RatePolicy,
+    pub upload: RatePolicy,     // upload & ganti gambar banner, buat banner multipart
+    pub attendance: RatePolicy, // clock-in / clock-out
+    pub auth: RatePolicy,       // token yang tidak dikenal, per IP
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            trust_proxy: false,
+            default: RatePolicy::new(120, 600),
+            upload: RatePolicy::new(10, 30),
+            attendance: RatePolicy::new(5, 10),
+            auth: RatePolicy::new(10, 10),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn policies(&self) -> [(&'static str, RatePolicy); 4] {
+        [
+            ("default", self.default),
+            ("upload", self.upload),
+            ("attendance", self.attendance),
+            ("auth", self.auth),
+        ]
+    }
+}
+
+pub async fn init_db(cfg: &DatabaseConfig) -> DatabaseConnection {
+    // Sudah dicek di Settings::validate
+    let mut options = ConnectOptions::new(cfg.url.clone().unwrap_or_default());
+    // Query dicatat lewat telemetry::record_query (level debug) bersama request id-nya
+    options.sqlx_logging(false);
+    let mut db = Database::connect(options)
         .await
-        .expect("Failed to connect to database")
+        .expect("Failed to connect to database");
+    db.set_metric_callback(telemetry::record_query);
+    db
+}
+
+pub fn media_access(cfg: &StorageConfig) -> MediaAccess {
+    MediaAccess::new(
+        cfg.private_prefixes.clone(),
+        cfg.signing_secret.clone().unwrap_or_default(),
+        cfg.signed_url_ttl_secs,
+    )
+}
+
+pub fn init_storage(cfg: &StorageConfig) -> Arc<dyn BlobStore> {
+    match cfg.backend.as_str() {
+        "s3" => {
+            let s3 = &cfg.s3;
+            let options = S3Options {
+                bucket: s3.bucket.clone().unwrap_or_default(),
+                region: s3.region.clone(),
+                endpoint: s3.endpoint.clone(),
+                access_key: s3.access_key.clone(),
+                secret_key: s3.secret_key.clone(),
+                path_style: s3.path_style.unwrap_or(s3.endpoint.is_some()),
+                public_url: s3.public_url.clone(),
+                presign_ttl_secs: s3.presign_ttl_secs,
+            };
+            let store = S3Store::new(options, media_access(cfg)).expect("Failed to configure S3 storage");
+            Arc::new(store)
+        }
+        _ => Arc::new(LocalStore::new(cfg.upload_dir.clone(), "/uploads", media_access(cfg))),
+    }
+}
+
+// Jadwal sweeper file upload yatim (default: tiap jam, masa tenggang 24 jam)
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct MediaGcConfig {
+    pub interval_secs: u64,
+    pub grace_secs: i64,
+}
+
+impl Default for MediaGcConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 3600,
+            grace_secs: 86400,
+        }
+    }
+}
+
+// Banner di trash dihapus permanen setelah masa retensi (default 30 hari)
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct TrashPurgeConfig {
+    pub interval_secs: u64,
+    pub retention_days: i64,
+}
+
+impl Default for TrashPurgeConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 3600,
+            retention_days: 30,
+        }
+    }
+}
+
+// Cache banner aktif di memori dan Cache-Control untuk client
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct HttpCacheConfig {
+    pub active_ttl_secs: u64,
+    pub active_max_age_secs: u64,
+}
+
+impl Default for HttpCacheConfig {
+    fn default() -> Self {
+        Self {
+            active_ttl_secs: 300,
+            active_max_age_secs: 60,
+        }
+    }
+}
+
+// Interval ping SSE supaya proxy tidak menutup koneksi yang diam
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct BannerStreamConfig {
+    pub heartbeat_secs: u64,
+}
+
+impl Default for BannerStreamConfig {
+    fn default() -> Self {
+        Self { heartbeat_secs: 15 }
+    }
+}
+
+// Pengiriman webhook: retry dengan exponential backoff (30s, 60s, 120s, ... maks 1 jam)
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub poll_interval_secs: u64,
+    pub timeout_secs: u64,
+    pub max_attempts: i32,
+    pub backoff_base_secs: i64,
+    pub backoff_max_secs: i64,
+    pub expiry_check_secs: u64, // cek banner published yang lewat end_date
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 5,
+            timeout_secs: 10,
+            max_attempts: 8,
+            backoff_base_secs: 30,
+            backoff_max_secs: 3600,
+            expiry_check_secs: 60,
+        }
+    }
+}
+
+// Graceful shutdown: readiness 503 dulu, tunggu readiness_delay_secs supaya load balancer
+// berhenti mengirim trafik, lalu request berjalan dan background job masing-masing
+// diberi waktu paling lama timeout_secs
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ShutdownConfig {
+    pub timeout_secs: u64,
+    pub readiness_delay_secs: u64,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 30,
+            readiness_delay_secs: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::dev::ServiceResponse;
+    use actix_web::http::{StatusCode, header};
+    use actix_web::App;
+    use actix_web::test::{TestRequest, call_service, init_service};
+
+    use crate::routes;
+
+    fn restricted() -> CorsConfig {
+        CorsConfig {
+            allowed_origins: vec!["https://app.example.com".to_string()],
+            ..CorsConfig::default()
+        }
+    }
+
+    fn preflight(origin: &str, method: &str) -> TestRequest {
+        TestRequest::default()
+            .method(Method::OPTIONS)
+            .uri("/api/banners/1")
+            .insert_header((header::ORIGIN, origin))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_METHOD, method))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization, content-type"))
+    }
+
+    fn header<B>(res: &ServiceResponse<B>, name: HeaderName) -> &str {
+        res.headers().get(name).and_then(|v| v.to_str().ok()).unwrap_or_default()
+    }
+
+    #[actix_web::test]
+    async fn preflight_allows_configured_origin_for_banner_put_and_delete() {
+        let app = init_service(App::new().wrap(cors(&restricted())).configure(routes::configure)).await;
+
+        for method in ["PUT", "DELETE"] {
+            let res = call_service(&app, preflight("https://app.example.com", method).to_request()).await;
+            assert_eq!(res.status(), StatusCode::OK, "{method}");
+            assert_eq!(header(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN), "https://app.example.com");
+            assert!(header(&res, header::ACCESS_CONTROL_ALLOW_METHODS).contains(method));
+            let allowed = header(&res, header::ACCESS_CONTROL_ALLOW_HEADERS).to_lowercase();
+            assert!(allowed.contains("authorization") && allowed.contains("content-type"));
+            assert_eq!(header(&res, header::ACCESS_CONTROL_MAX_AGE), "3600");
+        }
+    }
+
+    #[actix_web::test]
+    async fn preflight_rejects_unknown_origin_and_method() {
+        let app = init_service(App::new().wrap(cors(&restricted())).configure(routes::configure)).await;
+
+        let res = call_service(&app, preflight("https://evil.example.com", "DELETE").to_request()).await;
+        assert!(res.status().is_client_error());
+        assert!(!res.headers().contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        let res = call_service(&app, preflight("https://app.example.com", "PATCH").to_request()).await;
+        assert!(res.status().is_client_error());
+        assert!(!res.headers().contains_key(header::ACCESS_CONTROL_ALLOW_METHODS));
+    }
+
+    #[actix_web::test]
+    async fn wildcard_origin_answers_any_preflight_with_star() {
+        let app = init_service(App::new().wrap(cors(&CorsConfig::default())).configure(routes::configure)).await;
+
+        let res = call_service(&app, preflight("https://anywhere.example.org", "PUT").to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(header(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN), "*");
+    }
+
+    #[test]
+    fn rejects_wildcard_with_credentials_and_malformed_origins() {
+        let cfg = CorsConfig {
+            allowed_origins: vec!["*".to_string(), "https://app.example.com/path".to_string()],
+            allowed_methods: vec!["GET".to_string(), "BAD METHOD".to_string()],
+            allow_credentials: true,
+            ..CorsConfig::default()
+        };
+        let problems = cfg.problems();
+        assert_eq!(problems.len(), 3, "{problems:?}");
+        assert!(CorsConfig::default().problems().is_empty());
+        assert!(restricted().problems().is_empty());
+    }
 }
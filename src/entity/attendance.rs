@@ -8,6 +8,7 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub user_id: String,
+    pub department: Option<String>,
     pub clock_in_time: NaiveDateTime,
     pub clock_out_time: Option<NaiveDateTime>,
     pub created_at: Option<NaiveDateTime>,
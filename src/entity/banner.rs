@@ -9,12 +9,24 @@ pub struct Model {
     pub id: i32,
     pub title: Option<String>,
     pub content: String,
+    pub content_html: Option<String>,
+    pub cta_label: Option<String>,
+    pub cta_url: Option<String>,
     pub image_url: Option<String>,
+    pub image_thumbnail_url: Option<String>,
+    pub image_medium_url: Option<String>,
+    pub image_webp_url: Option<String>,
     pub start_date: NaiveDateTime,
     pub end_date: NaiveDateTime,
     pub is_active: bool,
+    pub status: String,
+    pub recurrence_rule: Option<String>,
+    pub recurrence_duration_minutes: Option<i32>,
+    pub recurrence_timezone: Option<String>,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub expiry_notified_at: Option<NaiveDateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
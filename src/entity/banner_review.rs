@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+use chrono::NaiveDateTime;
+use serde::{Serialize, Deserialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "banner_review")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub banner_id: i32,
+    pub from_status: String,
+    pub to_status: String,
+    pub actor: String,
+    pub comment: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
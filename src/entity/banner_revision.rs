@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+use chrono::NaiveDateTime;
+use serde::{Serialize, Deserialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "banner_revision")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub banner_id: i32,
+    pub revision: i32,
+    pub action: String,
+    pub changed_by: Option<String>,
+    pub snapshot: Json,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
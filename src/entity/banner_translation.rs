@@ -0,0 +1,22 @@
+use sea_orm::entity::prelude::*;
+use chrono::NaiveDateTime;
+use serde::{Serialize, Deserialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "banner_translation")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub banner_id: i32,
+    pub locale: String,
+    pub title: Option<String>,
+    pub content: String,
+    pub content_html: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
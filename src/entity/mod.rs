@@ -1,2 +1,8 @@
 pub mod attendance;
-pub mod banner;
\ No newline at end of file
+pub mod banner;
+pub mod banner_review;
+pub mod banner_revision;
+pub mod banner_translation;
+pub mod media;
+pub mod webhook_delivery;
+pub mod webhook_subscription;
\ No newline at end of file
@@ -0,0 +1,150 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+use crate::cache::ActiveBanners;
+use crate::schedule;
+
+// Jumlah event terakhir yang disimpan untuk resume lewat Last-Event-ID
+const HISTORY_LEN: usize = 100;
+
+#[derive(Clone, PartialEq, Serialize)]
+pub struct ActiveEntry {
+    pub id: i32,
+    pub version: String,
+}
+
+/// Banner yang sedang tampil; `current_id` adalah yang dikembalikan `/api/banners/active`.
+#[derive(Clone, PartialEq, Serialize)]
+pub struct ActiveState {
+    pub current_id: Option<i32>,
+    pub banners: Vec<ActiveEntry>,
+}
+
+impl ActiveState {
+    pub fn at(active: &ActiveBanners, now: NaiveDateTime) -> Self {
+        let banners: Vec<ActiveEntry> = active
+            .candidates
+            .iter()
+            .filter(|b| b.start_date <= now && b.end_date > now && schedule::is_visible(b, now))
+            .map(|b| {
+                // Versi ikut berubah kalau salah satu terjemahannya diubah
+                let updated = active
+                    .translations
+                    .iter()
+                    .filter(|t| t.banner_id == b.id)
+                    .filter_map(|t| t.updated_at)
+                    .chain(b.updated_at)
+                    .max();
+                ActiveEntry {
+                    id: b.id,
+                    version: updated
+                        .map(|u| u.and_utc().timestamp_millis())
+                        .unwrap_or(0)
+                        .to_string(),
+                }
+            })
+            .collect();
+        Self {
+            current_id: banners.first().map(|b| b.id),
+            banners,
+        }
+    }
+}
+
+/// Saat berikutnya himpunan banner aktif bisa berubah tanpa ada write:
+/// start/end date terlewati atau jendela jadwal berulang buka/tutup.
+pub fn next_boundary(active: &ActiveBanners, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let mut next: Option<NaiveDateTime> = None;
+    let mut consider = |t: NaiveDateTime| {
+        if t > now && next.is_none_or(|n| t < n) {
+            next = Some(t);
+        }
+    };
+
+    for b in &active.candidates {
+        consider(b.start_date);
+        consider(b.end_date);
+        if let Some(Ok(rec)) = schedule::recurrence_of(b) {
+            if let Some((_, end)) = rec.window_at(now) {
+                consider(end);
+            }
+            if let Some(start) = rec.next_start(now) {
+                consider(start);
+            }
+        }
+    }
+    next
+}
+
+pub struct Event {
+    pub id: u64,
+    pub data: String,
+}
+
+impl Event {
+    // Format wire SSE
+    pub fn to_sse(&self) -> String {
+        format!("id: {}\nevent: active_banners\ndata: {}\n\n", self.id, self.data)
+    }
+}
+
+/// Broadcast perubahan banner aktif ke semua koneksi `/api/banners/stream`.
+pub struct BannerEvents {
+    tx: broadcast::Sender<Arc<Event>>,
+    history: Mutex<VecDeque<Arc<Event>>>,
+    next_id: AtomicU64,
+}
+
+impl Default for BannerEvents {
+    fn default() -> Self {
+        Self {
+            tx: broadcast::channel(HISTORY_LEN).0,
+            history: Mutex::new(VecDeque::with_capacity(HISTORY_LEN)),
+            next_id: AtomicU64::new(1),
+        }
+    }
+}
+
+impl BannerEvents {
+    pub fn publish(&self, state: &ActiveState) {
+        let data = serde_json::to_string(state).unwrap_or_default();
+        let mut history = self.history.lock().unwrap();
+        let event = Arc::new(Event {
+            id: self.next_id.fetch_add(1, Ordering::SeqCst),
+            data,
+        });
+        if history.len() == HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back(event.clone());
+        // Tidak ada subscriber bukan error
+        let _ = self.tx.send(event);
+    }
+
+    /// Subscribe and collect what the client has to see first.
+    ///
+    /// With a `Last-Event-ID` still in history only the newer events are replayed;
+    /// otherwise (new client, or an id older than history) the latest state is sent.
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> (Vec<Arc<Event>>, broadcast::Receiver<Arc<Event>>) {
+        let history = self.history.lock().unwrap();
+        let rx = self.tx.subscribe();
+        let replay = match last_event_id {
+            Some(last)
+                if last < self.next_id.load(Ordering::SeqCst)
+                    && history.front().is_some_and(|e| e.id <= last + 1) =>
+            {
+                history.iter().filter(|e| e.id > last).cloned().collect()
+            }
+            _ => history.back().cloned().into_iter().collect(),
+        };
+        (replay, rx)
+    }
+
+    pub fn latest(&self) -> Option<Arc<Event>> {
+        self.history.lock().unwrap().back().cloned()
+    }
+}
@@ -1,19 +1,40 @@
-use actix_web::{HttpResponse, web};
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse, web};
 use actix_multipart::Multipart;
-use futures_util::TryStreamExt as _;
-use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
 use uuid::Uuid;
-use std::path::Path;
 use chrono::Utc;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    TransactionTrait,
 };
 use serde::Serialize;
-use crate::entity::{attendance, banner};
-use crate::models::{AttendanceDto, ClockRequest, CreateBannerRequest, UpdateBannerRequest, BannerDto};
+use crate::auth::{self, Actor};
+use crate::cache::{self, ActiveBannerCache};
+use crate::entity::{
+    attendance, banner, banner_review, banner_revision, banner_translation, webhook_delivery,
+    webhook_subscription,
+};
+use crate::config::{Settings, UploadConfig};
+use crate::events::BannerEvents;
+use crate::images;
+use crate::uploads;
+use crate::locale::{self, DefaultLocale};
+use crate::markdown;
+use crate::media::{self, SweepReport};
+use crate::metrics::Metrics;
+use crate::webhooks;
+use crate::presence::{self, PresenceHub, PresenceMessage};
+use crate::revisions;
+use crate::schedule::{self, Recurrence};
+use crate::shutdown::Shutdown;
+use crate::validation::{self, JsonOrMultipart, ValidJson};
+use crate::storage::{self as store, BlobStore};
+use crate::workflow::{BannerStatus, Transition};
+use crate::models::{AttendanceDto, ClockRequest, CreateBannerRequest, UpdateBannerRequest, BannerDto, BannerImageDto, BannerRevisionDto, BannerReviewDto, BannerTranslationDto, TransitionRequest, UpsertTranslationRequest, CreateWebhookRequest, UpdateWebhookRequest, WebhookDto, WebhookDeliveryDto};
+use crate::openapi::{CreateBannerForm, ImageUpload, MessageBody, ValidationErrors};
 use crate::responses::ApiResponse;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use tokio::sync::broadcast::error::RecvError;
 
 #[derive(Serialize)]
 struct ErrorResponse<'a> {
@@ -21,14 +42,24 @@ struct ErrorResponse<'a> {
     message: &'a str,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/attendance/clockin",
+    tag = "attendance",
+    summary = "Clock in",
+    request_body = ClockRequest,
+    responses(
+        (status = 200, description = "Clock-in recorded", body = ApiResponse<AttendanceDto>),
+        (status = 409, description = "User already clocked in", body = MessageBody),
+        (status = 422, description = "Validation failed", body = ValidationErrors),
+    )
+)]
 pub async fn clock_in(
     db: web::Data<sea_orm::DatabaseConnection>,
-    payload: web::Json<ClockRequest>,
+    presence: web::Data<PresenceHub>,
+    payload: ValidJson<ClockRequest>,
 ) -> HttpResponse {
     let user = payload.user_id.trim();
-    if user.is_empty() {
-        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Invalid user_id"));
-    }
 
     // Cek apakah user sudah clock in tapi belum clock out
     match attendance::Entity::find()
@@ -49,9 +80,17 @@ pub async fn clock_in(
         }
     }
 
+    let department = payload
+        .department
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string);
+    
     let now = Utc::now().naive_utc();
     let model = attendance::ActiveModel {
         user_id: Set(user.to_string()),
+        department: Set(department),
         clock_in_time: Set(now),
         clock_out_time: Set(None),
         created_at: Set(Some(now)),
@@ -59,11 +98,22 @@ pub async fn clock_in(
         ..Default::default()
     };
 
-    match model.insert(db.get_ref()).await {
+    let result: Result<attendance::Model, DbErr> = async {
+        let txn = db.begin().await?;
+        let inserted = model.insert(&txn).await?;
+        webhooks::enqueue(&txn, webhooks::CLOCK_IN, webhooks::attendance_data(&inserted)).await?;
+        txn.commit().await?;
+        Ok(inserted)
+    }
+    .await;
+
+    match result {
         Ok(inserted) => {
+            presence.clock_in(&inserted);
             let dto = AttendanceDto {
                 id: inserted.id,
                 user_id: inserted.user_id,
+                department: inserted.department,
                 clock_in_time: inserted.clock_in_time,
                 clock_out_time: inserted.clock_out_time,
                 created_at: inserted.created_at,
@@ -76,14 +126,24 @@ pub async fn clock_in(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/attendance/clockout",
+    tag = "attendance",
+    summary = "Clock out",
+    request_body = ClockRequest,
+    responses(
+        (status = 200, description = "Clock-out recorded", body = ApiResponse<AttendanceDto>),
+        (status = 404, description = "No open clock-in session", body = MessageBody),
+        (status = 422, description = "Validation failed", body = ValidationErrors),
+    )
+)]
 pub async fn clock_out(
     db: web::Data<sea_orm::DatabaseConnection>,
-    payload: web::Json<ClockRequest>,
+    presence: web::Data<PresenceHub>,
+    payload: ValidJson<ClockRequest>,
 ) -> HttpResponse {
     let user = payload.user_id.trim();
-    if user.is_empty() {
-        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Invalid user_id"));
-    }
 
     // Cari clock-in terakhir yang belum clock-out
     match attendance::Entity::find()
@@ -99,11 +159,23 @@ pub async fn clock_out(
             active.clock_out_time = Set(Some(now));
             active.updated_at = Set(Some(now));
 
-            match active.update(db.get_ref()).await {
+            let result: Result<attendance::Model, DbErr> = async {
+                let txn = db.begin().await?;
+                let updated = active.update(&txn).await?;
+                webhooks::enqueue(&txn, webhooks::CLOCK_OUT, webhooks::attendance_data(&updated))
+                    .await?;
+                txn.commit().await?;
+                Ok(updated)
+            }
+            .await;
+
+            match result {
                 Ok(updated) => {
+                    presence.clock_out(&updated);
                     let dto = AttendanceDto {
                         id: updated.id,
                         user_id: updated.user_id,
+                        department: updated.department,
                         clock_in_time: updated.clock_in_time,
                         clock_out_time: updated.clock_out_time,
                         created_at: updated.created_at,
@@ -124,6 +196,20 @@ pub async fn clock_out(
 }
 
 
+#[utoipa::path(
+    get,
+    path = "/api/attendance",
+    tag = "attendance",
+    summary = "Attendance history",
+    params(
+        ("user_id" = Option<String>, Query, description = "Filter by user"),
+        ("limit" = Option<u64>, Query, description = "Default 50"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Vec<AttendanceDto>>),
+        (status = 404, description = "No attendance records", body = MessageBody),
+    )
+)]
 pub async fn get_history(
     db: web::Data<sea_orm::DatabaseConnection>,
     query: web::Query<HashMap<String, String>>,
@@ -154,6 +240,7 @@ pub async fn get_history(
                 .map(|r| AttendanceDto {
                     id: r.id,
                     user_id: r.user_id,
+                    department: r.department,
                     clock_in_time: r.clock_in_time,
                     clock_out_time: r.clock_out_time,
                     created_at: r.created_at,
@@ -170,125 +257,580 @@ pub async fn get_history(
     }
 }
 
-// Banner handlers
-pub async fn upload_banner_image(mut multipart: Multipart) -> HttpResponse {
-    while let Some(field) = multipart.try_next().await.unwrap_or(None) {
-        let content_disposition = field.content_disposition();
-        
-        if let Some(content_disposition) = content_disposition {
-            if let Some(filename) = content_disposition.get_filename() {
-            let file_extension = Path::new(filename)
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .unwrap_or("jpg");
-            
-            // Generate unique filename
-            let new_filename = format!("{}.{}", Uuid::new_v4(), file_extension);
-            let filepath = format!("uploads/banners/{}", new_filename);
-            
-            // Create uploads directory if it doesn't exist
-            if let Err(_) = tokio::fs::create_dir_all("uploads/banners").await {
-                return HttpResponse::InternalServerError()
-                    .json(ApiResponse::<()>::error("Failed to create upload directory"));
+// Papan presence: snapshot sesi terbuka saat connect, lalu event clock-in/clock-out.
+// Filter opsional `?department=`, token boleh lewat header atau `?access_token=`.
+// Hanya admin yang melihat semua departemen; token lain dibatasi ke departemennya sendiri.
+#[utoipa::path(
+    get,
+    path = "/api/attendance/presence",
+    tag = "attendance",
+    summary = "WebSocket presence feed",
+    params(
+        ("department" = Option<String>, Query, description = "Only sessions of this department; non-admin tokens are always limited to their own department"),
+        ("access_token" = Option<String>, Query, description = "API token for browsers that cannot set Authorization"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 101, description = "Switching to WebSocket; messages are JSON `snapshot`, `clock_in` and `clock_out`"),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 403, description = "Token has no department, or asks for another department", body = MessageBody),
+    )
+)]
+pub async fn presence_ws(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    presence: web::Data<PresenceHub>,
+    shutdown: web::Data<Shutdown>,
+    actor: Option<Actor>,
+    req: HttpRequest,
+    body: web::Payload,
+    query: web::Query<HashMap<String, String>>,
+) -> HttpResponse {
+    let Some(actor) = actor.or_else(|| auth::actor_from_query(&req, &query)) else {
+        return HttpResponse::Unauthorized()
+            .json(ApiResponse::<()>::error("Missing or invalid API token"));
+    };
+    let requested = query
+        .get("department")
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty());
+    let department = if actor.is_admin() {
+        requested
+    } else {
+        match (actor.department, requested) {
+            (None, _) => return auth::forbidden("Presence requires a token scoped to a department"),
+            (Some(own), Some(other)) if own != other => {
+                return auth::forbidden("Presence is limited to your own department");
+            }
+            (own, _) => own,
+        }
+    };
+    
+    let (response, mut session, mut messages) = match actix_ws::handle(&req, body) {
+        Ok(ws) => ws,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(&e.to_string()));
+        }
+    };
+    
+    // Subscribe sebelum snapshot supaya tidak ada event yang terlewat
+    let mut rx = presence.subscribe();
+    actix_web::rt::spawn(async move {
+        let dept = department.as_deref();
+        let mut heartbeat = tokio::time::interval(std::time::Duration::from_secs(30));
+        let mut resync = true;
+        loop {
+            if resync {
+                resync = false;
+                let snapshot = match presence::snapshot(db.get_ref(), dept).await {
+                    Ok(snapshot) => snapshot,
+                    Err(e) => {
+                        tracing::error!(error = %e, "Presence snapshot failed");
+                        break;
+                    }
+                };
+                if !send_presence(&mut session, &snapshot).await {
+                    return;
+                }
             }
             
-            // Save file
-            match File::create(&filepath).await {
-                Ok(mut f) => {
-                    let mut field_stream = field;
-                    while let Some(chunk) = field_stream.try_next().await.unwrap_or(None) {
-                        if f.write_all(&chunk).await.is_err() {
-                            return HttpResponse::InternalServerError()
-                                .json(ApiResponse::<()>::error("Failed to write file"));
+            tokio::select! {
+                msg = messages.recv() => match msg {
+                    Some(Ok(actix_ws::Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            return;
                         }
                     }
-                    
-                    let response_data = serde_json::json!({
-                        "image_url": format!("/{}", filepath)
-                    });
-                    
-                    return HttpResponse::Ok()
-                        .json(ApiResponse::success("Image uploaded successfully", Some(response_data)));
+                    Some(Ok(actix_ws::Message::Close(reason))) => {
+                        let _ = session.close(reason).await;
+                        return;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(_)) | None => break,
+                },
+                event = rx.recv() => match event {
+                    Ok(event) => {
+                        if event.matches(dept) && !send_presence(&mut session, &event).await {
+                            return;
+                        }
+                    }
+                    // Tertinggal terlalu jauh: kirim ulang snapshot lengkap
+                    Err(RecvError::Lagged(_)) => resync = true,
+                    Err(RecvError::Closed) => break,
+                },
+                _ = heartbeat.tick() => {
+                    if session.ping(b"").await.is_err() {
+                        return;
+                    }
                 }
-                Err(_) => {
-                    return HttpResponse::InternalServerError()
-                        .json(ApiResponse::<()>::error("Failed to create file"));
+                // Server akan berhenti: 1001 supaya client reconnect ke instance lain
+                _ = shutdown.draining() => {
+                    let _ = session.close(Some(actix_ws::CloseCode::Away.into())).await;
+                    return;
                 }
             }
-            }
         }
+        let _ = session.close(None).await;
+    });
+    
+    response
+}
+
+// false kalau koneksi sudah tertutup
+async fn send_presence(session: &mut actix_ws::Session, message: &PresenceMessage) -> bool {
+    match serde_json::to_string(message) {
+        Ok(json) => session.text(json).await.is_ok(),
+        Err(e) => {
+            tracing::error!(error = %e, "Presence serialize failed");
+            true
+        }
+    }
+}
+
+// Banner handlers
+
+// Key di blob store untuk setiap varian gambar banner
+struct StoredImage {
+    upload_id: Uuid,
+    original: String,
+    thumbnail: String,
+    medium: String,
+    webp: String,
+}
+
+impl StoredImage {
+    // Susun ulang dari key milik satu upload (lihat media::upload_keys)
+    fn from_keys(upload_id: Uuid, keys: &[String]) -> Option<Self> {
+        let find = |name: &str, webp: bool| {
+            keys.iter()
+                .find(|k| {
+                    let file = k.rsplit('/').next().unwrap_or_default();
+                    file.starts_with(&format!("{}.", name)) && file.ends_with(".webp") == webp
+                })
+                .cloned()
+        };
+        Some(Self {
+            upload_id,
+            original: find("original", false)?,
+            thumbnail: find("thumbnail", false)?,
+            medium: find("medium", false)?,
+            webp: find("original", true)?,
+        })
+    }
+
+    fn keys(&self) -> Vec<String> {
+        vec![
+            self.original.clone(),
+            self.thumbnail.clone(),
+            self.medium.clone(),
+            self.webp.clone(),
+        ]
+    }
+
+    fn apply(&self, active: &mut banner::ActiveModel) {
+        active.image_url = Set(Some(self.original.clone()));
+        active.image_thumbnail_url = Set(Some(self.thumbnail.clone()));
+        active.image_medium_url = Set(Some(self.medium.clone()));
+        active.image_webp_url = Set(Some(self.webp.clone()));
+    }
+
+    async fn to_dto(&self, store: &dyn BlobStore) -> BannerImageDto {
+        BannerImageDto {
+            upload_id: self.upload_id,
+            image_url: store::resolve_url(store, Some(self.original.clone())).await,
+            image_thumbnail_url: store::resolve_url(store, Some(self.thumbnail.clone())).await,
+            image_medium_url: store::resolve_url(store, Some(self.medium.clone())).await,
+            image_webp_url: store::resolve_url(store, Some(self.webp.clone())).await,
+        }
+    }
+}
+
+// Gambar dari `image_upload_id`; 422 kalau upload tidak dikenal atau sudah dibersihkan GC
+async fn referenced_image(
+    db: &sea_orm::DatabaseConnection,
+    upload_id: Uuid,
+) -> Result<StoredImage, HttpResponse> {
+    let keys = media::upload_keys(db, upload_id).await.map_err(|e| {
+        HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&format!("DB error: {}", e)))
+    })?;
+    StoredImage::from_keys(upload_id, &keys).ok_or_else(expired_upload)
+}
+
+// Dibaca di luar transaksi, jadi GC masih bisa menghapusnya sebelum acquire(); cek ulang di sana
+fn expired_upload() -> HttpResponse {
+    validation::field_error("image_upload_id", "unknown or expired upload")
+}
+
+// Masa berlaku terpendek dari URL gambar banner; None kalau semuanya permanen
+fn image_url_ttl(store: &dyn BlobStore, banner: &banner::Model) -> Option<u32> {
+    media::banner_keys(banner)
+        .iter()
+        .filter(|key| !store::is_url(key))
+        .filter_map(|key| store.url_ttl(key))
+        .min()
+}
+
+// Banner yang belum masuk trash
+async fn find_live_banner(
+    db: &sea_orm::DatabaseConnection,
+    id: i32,
+) -> Result<Option<banner::Model>, DbErr> {
+    banner::Entity::find_by_id(id)
+        .filter(banner::Column::DeletedAt.is_null())
+        .one(db)
+        .await
+}
+
+// Kolom image_* menyimpan key blob store, ubah jadi URL sebelum dikirim ke client
+async fn banner_dto(store: &dyn BlobStore, model: banner::Model) -> BannerDto {
+    let mut dto = BannerDto::from(model);
+    // Banner lama belum punya content_html tersimpan
+    if dto.content_html.is_none() {
+        dto.content_html = Some(markdown::render(&dto.content));
+    }
+    dto.image_url = store::resolve_url(store, dto.image_url).await;
+    dto.image_thumbnail_url = store::resolve_url(store, dto.image_thumbnail_url).await;
+    dto.image_medium_url = store::resolve_url(store, dto.image_medium_url).await;
+    dto.image_webp_url = store::resolve_url(store, dto.image_webp_url).await;
+    dto
+}
+
+// Ganti title/content dengan terjemahan terbaik sesuai urutan locale di `chain`
+async fn localize(
+    db: &sea_orm::DatabaseConnection,
+    dtos: &mut [BannerDto],
+    chain: &[String],
+) -> Result<(), DbErr> {
+    let ids: Vec<i32> = dtos.iter().map(|d| d.id).collect();
+    if ids.is_empty() || chain.is_empty() {
+        return Ok(());
     }
     
-    HttpResponse::BadRequest().json(ApiResponse::<()>::error("No file provided"))
+    let translations = banner_translation::Entity::find()
+        .filter(banner_translation::Column::BannerId.is_in(ids))
+        .filter(banner_translation::Column::Locale.is_in(chain.iter().cloned()))
+        .all(db)
+        .await?;
+    
+    for dto in dtos.iter_mut() {
+        if let Some(t) = best_translation(&translations, dto.id, chain) {
+            apply_translation(dto, t);
+        }
+    }
+    Ok(())
+}
+
+// Terjemahan dengan locale paling depan di `chain`
+fn best_translation<'a>(
+    translations: &'a [banner_translation::Model],
+    banner_id: i32,
+    chain: &[String],
+) -> Option<&'a banner_translation::Model> {
+    translations
+        .iter()
+        .filter(|t| t.banner_id == banner_id)
+        .filter_map(|t| chain.iter().position(|l| *l == t.locale).map(|pos| (pos, t)))
+        .min_by_key(|(pos, _)| *pos)
+        .map(|(_, t)| t)
+}
+
+fn apply_translation(dto: &mut BannerDto, t: &banner_translation::Model) {
+    if t.title.is_some() {
+        dto.title = t.title.clone();
+    }
+    dto.content = t.content.clone();
+    dto.content_html = Some(
+        t.content_html
+            .clone()
+            .unwrap_or_else(|| markdown::render(&t.content)),
+    );
+    dto.locale = Some(t.locale.clone());
+}
+
+fn upload_limits(req: &HttpRequest) -> UploadConfig {
+    req.app_data::<web::Data<Settings>>()
+        .map(|settings| settings.uploads.clone())
+        .unwrap_or_default()
+}
+
+// Baca field file pertama dari multipart, validasi & proses gambar, lalu simpan semua varian
+async fn save_banner_image(
+    db: &sea_orm::DatabaseConnection,
+    store: &dyn BlobStore,
+    metrics: &Metrics,
+    req: &HttpRequest,
+    multipart: &mut Multipart,
+) -> Result<StoredImage, HttpResponse> {
+    let limits = upload_limits(req);
+    let bytes = uploads::read_file(req, multipart, &limits)
+        .await
+        .map_err(|e| e.response())?;
+    let received = bytes.len();
+    let (image, written) = store_banner_image(store, bytes, limits.max_image_dimension).await?;
+
+    // Catat di tabel media (ref_count 0) supaya bisa dibersihkan GC kalau tidak dipakai
+    if let Err(e) = media::register(db, &written).await {
+        delete_blobs(store, &written).await;
+        return Err(HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("DB error: {}", e))));
+    }
+    metrics.record_upload(received, written.iter().map(|(_, len)| len).sum());
+    Ok(image)
+}
+
+// Proses gambar lalu tulis semua varian ke blob store; belum dicatat di tabel media
+async fn store_banner_image(
+    store: &dyn BlobStore,
+    bytes: Vec<u8>,
+    max_dimension: u32,
+) -> Result<(StoredImage, Vec<(String, usize)>), HttpResponse> {
+    let processed = match web::block(move || images::process(&bytes, max_dimension)).await {
+        Ok(Ok(processed)) => processed,
+        Ok(Err(e)) => {
+            return Err(HttpResponse::BadRequest().json(ApiResponse::<()>::error(&e.to_string())));
+        }
+        Err(_) => {
+            return Err(HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("Failed to process image")));
+        }
+    };
+
+    let upload_id = Uuid::new_v4();
+    let prefix = media::upload_prefix(upload_id);
+    let mut written: Vec<(String, usize)> = Vec::new();
+    for variant in processed.variants() {
+        let key = format!("{}/{}", prefix, variant.filename());
+        if let Err(e) = store.put(&key, &variant.bytes, variant.content_type).await {
+            // Hapus varian yang sudah tersimpan supaya tidak jadi file yatim
+            delete_blobs(store, &written).await;
+            return Err(HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&e.to_string())));
+        }
+        written.push((key, variant.bytes.len()));
+    }
+
+    let image = StoredImage {
+        upload_id,
+        original: format!("{}/{}", prefix, processed.original.filename()),
+        thumbnail: format!("{}/{}", prefix, processed.thumbnail.filename()),
+        medium: format!("{}/{}", prefix, processed.medium.filename()),
+        webp: format!("{}/{}", prefix, processed.webp.filename()),
+    };
+    Ok((image, written))
+}
+
+async fn delete_blobs(store: &dyn BlobStore, written: &[(String, usize)]) {
+    for (key, _) in written {
+        if let Err(e) = store.delete(key).await {
+            tracing::warn!(key = %key, error = %e, "Failed to delete orphaned upload");
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/banners/upload",
+    tag = "banners",
+    summary = "Upload a banner image",
+    request_body(content = ImageUpload, content_type = "multipart/form-data"),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Image stored", body = ApiResponse<BannerImageDto>),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 400, description = "Invalid request", body = MessageBody),
+        (status = 413, description = "File or request body too large", body = MessageBody),
+    )
+)]
+pub async fn upload_banner_image(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    metrics: web::Data<Metrics>,
+    _actor: Actor,
+    req: HttpRequest,
+    mut multipart: Multipart,
+) -> HttpResponse {
+    match save_banner_image(db.get_ref(), store.get_ref(), metrics.get_ref(), &req, &mut multipart).await {
+        Ok(image) => HttpResponse::Ok().json(ApiResponse::success(
+            "Image uploaded successfully",
+            Some(image.to_dto(store.get_ref()).await),
+        )),
+        Err(resp) => resp,
+    }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/banners",
+    tag = "banners",
+    summary = "Create a banner as draft",
+    description = "Accepts JSON, or multipart with a `data` part (the same JSON) and an `image` file; \
+                   the image and the banner are stored together or not at all. An earlier \
+                   `POST /api/banners/upload` can be attached with `image_upload_id`.",
+    request_body(content(
+        (CreateBannerRequest = "application/json"),
+        (CreateBannerForm = "multipart/form-data"),
+    )),
+    security(("bearer" = [])),
+    responses(
+        (status = 201, description = "Banner created", body = ApiResponse<BannerDto>),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 400, description = "Invalid request", body = MessageBody),
+        (status = 413, description = "File or request body too large", body = MessageBody),
+        (status = 422, description = "Validation failed", body = ValidationErrors),
+    )
+)]
 pub async fn create_banner(
     db: web::Data<sea_orm::DatabaseConnection>,
-    payload: web::Json<CreateBannerRequest>,
+    store: web::Data<dyn BlobStore>,
+    metrics: web::Data<Metrics>,
+    actor: Actor,
+    req: HttpRequest,
+    body: JsonOrMultipart<CreateBannerRequest>,
 ) -> HttpResponse {
-    let start_date = match chrono::NaiveDateTime::parse_from_str(&payload.start_date, "%Y-%m-%d %H:%M:%S") {
-        Ok(date) => date,
-        Err(_) => {
-            return HttpResponse::BadRequest()
-                .json(ApiResponse::<()>::error("Invalid start_date format. Use: YYYY-MM-DD HH:MM:SS"));
+    let limits = upload_limits(&req);
+    let (payload, file) = match body {
+        JsonOrMultipart::Json(payload) => (payload, None),
+        JsonOrMultipart::Multipart(mut multipart) => {
+            let form = match uploads::read_form(&req, &mut multipart, &limits).await {
+                Ok(form) => form,
+                Err(e) => return e.response(),
+            };
+            let Some(data) = form.fields.get("data") else {
+                return validation::field_error("data", "is required");
+            };
+            match validation::parse_json::<CreateBannerRequest>(data.as_bytes()) {
+                Ok(payload) => (payload, form.file),
+                Err(errors) => return validation::error_response(errors),
+            }
         }
     };
+    if file.is_some() && payload.image_upload_id.is_some() {
+        return validation::field_error("image_upload_id", "cannot be combined with an image file");
+    }
+
+    let (start_date, end_date) = (payload.start_date, payload.end_date);
     
-    let end_date = match chrono::NaiveDateTime::parse_from_str(&payload.end_date, "%Y-%m-%d %H:%M:%S") {
-        Ok(date) => date,
-        Err(_) => {
-            return HttpResponse::BadRequest()
-                .json(ApiResponse::<()>::error("Invalid end_date format. Use: YYYY-MM-DD HH:MM:SS"));
-        }
+    // Jadwal berulang opsional, hanya berlaku di dalam rentang start_date..end_date.
+    // RRULE dan pasangan CTA sudah divalidasi validate_create_banner
+    let recurrence_rule = payload.recurrence_rule.clone().filter(|r| !r.trim().is_empty());
+    let (recurrence_duration_minutes, recurrence_timezone) = match recurrence_rule {
+        Some(_) => (payload.recurrence_duration_minutes, payload.recurrence_timezone.clone()),
+        None => (None, None),
     };
     
-    if start_date >= end_date {
-        return HttpResponse::BadRequest()
-            .json(ApiResponse::<()>::error("start_date must be before end_date"));
-    }
+    let cta_label = payload.cta_label.clone().filter(|l| !l.trim().is_empty());
+    let cta_url = payload.cta_url.clone().filter(|u| !u.trim().is_empty());
     
     let now = Utc::now().naive_utc();
-    let model = banner::ActiveModel {
+    let mut model = banner::ActiveModel {
         title: Set(payload.title.clone()),
         content: Set(payload.content.clone()),
+        content_html: Set(Some(markdown::render(&payload.content))),
+        cta_label: Set(cta_label.map(|l| l.trim().to_string())),
+        cta_url: Set(cta_url),
         image_url: Set(None),
         start_date: Set(start_date),
         end_date: Set(end_date),
         is_active: Set(true),
+        status: Set(BannerStatus::Draft.as_str().to_string()),
+        recurrence_rule: Set(recurrence_rule),
+        recurrence_duration_minutes: Set(recurrence_duration_minutes),
+        recurrence_timezone: Set(recurrence_timezone),
         created_at: Set(Some(now)),
         updated_at: Set(Some(now)),
         ..Default::default()
     };
     
-    match model.insert(db.get_ref()).await {
-        Ok(inserted) => {
-            let dto = BannerDto {
-                id: inserted.id,
-                title: inserted.title,
-                content: inserted.content,
-                image_url: inserted.image_url,
-                start_date: inserted.start_date,
-                end_date: inserted.end_date,
-                is_active: inserted.is_active,
-                created_at: inserted.created_at,
-                updated_at: inserted.updated_at,
-            };
-            HttpResponse::Created().json(ApiResponse::success("Banner created", Some(dto)))
+    // Gambar baru ditulis ke blob store dulu, baris media-nya ikut transaksi banner
+    let mut fresh: Vec<(String, usize)> = Vec::new();
+    let mut received = 0;
+    let image = match (file, payload.image_upload_id) {
+        (Some(bytes), _) => {
+            received = bytes.len();
+            match store_banner_image(store.get_ref(), bytes, limits.max_image_dimension).await {
+                Ok((image, written)) => {
+                    fresh = written;
+                    Some(image)
+                }
+                Err(resp) => return resp,
+            }
+        }
+        (None, Some(upload_id)) => match referenced_image(db.get_ref(), upload_id).await {
+            Ok(image) => Some(image),
+            Err(resp) => return resp,
+        },
+        (None, None) => None,
+    };
+    if let Some(ref image) = image {
+        image.apply(&mut model);
+    }
+    
+    // None: upload yang direferensikan dibersihkan GC sebelum sempat dipasang, transaksi di-rollback
+    let result: Result<Option<banner::Model>, DbErr> = async {
+        let txn = db.begin().await?;
+        let inserted = model.insert(&txn).await?;
+        if let Some(ref image) = image {
+            media::register(&txn, &fresh).await?;
+            let keys = image.keys();
+            if media::acquire(&txn, &keys).await? < keys.len() as u64 {
+                return Ok(None);
+            }
+        }
+        revisions::record(&txn, &inserted, revisions::CREATED, Some(&actor)).await?;
+        txn.commit().await?;
+        Ok(Some(inserted))
+    }
+    .await;
+    
+    match result {
+        Ok(None) => expired_upload(),
+        Ok(Some(inserted)) => {
+            if !fresh.is_empty() {
+                metrics.record_upload(received, fresh.iter().map(|(_, len)| len).sum());
+            }
+            let dto = banner_dto(store.get_ref(), inserted).await;
+            HttpResponse::Created().json(ApiResponse::success("Banner created as draft", Some(dto)))
+        }
+        Err(e) => {
+            // Banner tidak tersimpan: file yang baru ditulis juga dibuang
+            delete_blobs(store.get_ref(), &fresh).await;
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&format!("Insert error: {}", e)))
         }
-        Err(e) => HttpResponse::InternalServerError()
-            .json(ApiResponse::<()>::error(&format!("Insert error: {}", e))),
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/banners/{id}/image",
+    tag = "banners",
+    summary = "Replace the banner image",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+    ),
+    request_body(content = ImageUpload, content_type = "multipart/form-data"),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Banner image updated", body = ApiResponse<BannerDto>),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 400, description = "Invalid request", body = MessageBody),
+        (status = 404, description = "Banner not found", body = MessageBody),
+        (status = 409, description = "Image was garbage-collected before it could be attached", body = MessageBody),
+        (status = 413, description = "File or request body too large", body = MessageBody),
+    )
+)]
 pub async fn update_banner_image(
     db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    metrics: web::Data<Metrics>,
+    actor: Actor,
+    req: HttpRequest,
     path: web::Path<i32>,
     mut multipart: Multipart,
 ) -> HttpResponse {
     let banner_id = path.into_inner();
     
     // Check if banner exists
-    let banner_result = banner::Entity::find_by_id(banner_id).one(db.get_ref()).await;
+    let banner_result = find_live_banner(db.get_ref(), banner_id).await;
     let banner = match banner_result {
         Ok(Some(b)) => b,
         Ok(None) => {
@@ -301,75 +843,68 @@ pub async fn update_banner_image(
         }
     };
     
-    while let Some(field) = multipart.try_next().await.unwrap_or(None) {
-        let content_disposition = field.content_disposition();
-        
-        if let Some(content_disposition) = content_disposition {
-            if let Some(filename) = content_disposition.get_filename() {
-                let file_extension = Path::new(filename)
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .unwrap_or("jpg");
-            
-            let new_filename = format!("{}.{}", Uuid::new_v4(), file_extension);
-            let filepath = format!("uploads/banners/{}", new_filename);
-            
-            if let Err(_) = tokio::fs::create_dir_all("uploads/banners").await {
-                return HttpResponse::InternalServerError()
-                    .json(ApiResponse::<()>::error("Failed to create upload directory"));
-            }
-            
-            match File::create(&filepath).await {
-                Ok(mut f) => {
-                    let mut field_stream = field;
-                    while let Some(chunk) = field_stream.try_next().await.unwrap_or(None) {
-                        if f.write_all(&chunk).await.is_err() {
-                            return HttpResponse::InternalServerError()
-                                .json(ApiResponse::<()>::error("Failed to write file"));
-                        }
-                    }
-                    
-                    // Update banner with image URL
-                    let mut active: banner::ActiveModel = banner.into();
-                    active.image_url = Set(Some(format!("/{}", filepath)));
-                    active.updated_at = Set(Some(Utc::now().naive_utc()));
-                    
-                    match active.update(db.get_ref()).await {
-                        Ok(updated) => {
-                            let dto = BannerDto {
-                                id: updated.id,
-                                title: updated.title,
-                                content: updated.content,
-                                image_url: updated.image_url,
-                                start_date: updated.start_date,
-                                end_date: updated.end_date,
-                                is_active: updated.is_active,
-                                created_at: updated.created_at,
-                                updated_at: updated.updated_at,
-                            };
-                            return HttpResponse::Ok()
-                                .json(ApiResponse::success("Banner image updated", Some(dto)));
-                        }
-                        Err(e) => {
-                            return HttpResponse::InternalServerError()
-                                .json(ApiResponse::<()>::error(&format!("Update error: {}", e)));
-                        }
-                    }
-                }
-                Err(_) => {
-                    return HttpResponse::InternalServerError()
-                        .json(ApiResponse::<()>::error("Failed to create file"));
-                }
-            }
-            }
-        }
-    }
+    let image = match save_banner_image(db.get_ref(), store.get_ref(), metrics.get_ref(), &req, &mut multipart).await {
+        Ok(image) => image,
+        Err(resp) => return resp,
+    };
     
-    HttpResponse::BadRequest().json(ApiResponse::<()>::error("No file provided"))
+    match replace_banner_image(db.get_ref(), banner, &image, &actor).await {
+        Ok(None) => HttpResponse::Conflict()
+            .json(ApiResponse::<()>::error("Uploaded image was removed before it could be attached, retry the upload")),
+        Ok(Some(updated)) => HttpResponse::Ok()
+            .json(ApiResponse::success("Banner image updated", Some(banner_dto(store.get_ref(), updated).await))),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("Update error: {}", e))),
+    }
+}
+
+// Pasang gambar baru ke banner dan pindahkan referensi media dari gambar lama dalam satu transaksi.
+// None kalau file-nya sudah dibersihkan GC (transaksi di-rollback)
+async fn replace_banner_image(
+    db: &sea_orm::DatabaseConnection,
+    banner: banner::Model,
+    image: &StoredImage,
+    actor: &Actor,
+) -> Result<Option<banner::Model>, DbErr> {
+    let old_keys = media::banner_keys(&banner);
+    let txn = db.begin().await?;
+
+    let mut active: banner::ActiveModel = banner.into();
+    image.apply(&mut active);
+    require_review(&mut active, actor);
+    active.updated_at = Set(Some(Utc::now().naive_utc()));
+    let updated = active.update(&txn).await?;
+
+    let keys = image.keys();
+    if media::acquire(&txn, &keys).await? < keys.len() as u64 {
+        return Ok(None);
+    }
+    media::release(&txn, &old_keys).await?;
+    revisions::record(&txn, &updated, revisions::IMAGE_UPDATED, Some(actor)).await?;
+    txn.commit().await?;
+    Ok(Some(updated))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/banners",
+    tag = "banners",
+    summary = "List banners",
+    params(
+        ("status" = Option<String>, Query, description = "draft, pending_review, approved, published or archived"),
+        ("limit" = Option<u64>, Query, description = "Default 50"),
+        ("locale" = Option<String>, Query, description = "Override Accept-Language, mis. `en`"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Vec<BannerDto>>),
+        (status = 400, description = "Invalid request", body = MessageBody),
+    )
+)]
 pub async fn get_banners(
     db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    default_locale: web::Data<DefaultLocale>,
+    req: HttpRequest,
     query: web::Query<HashMap<String, String>>,
 ) -> HttpResponse {
     let limit = query
@@ -377,7 +912,15 @@ pub async fn get_banners(
         .and_then(|s| s.parse::<u64>().ok())
         .unwrap_or(50);
     
-    match banner::Entity::find()
+    let mut find = banner::Entity::find().filter(banner::Column::DeletedAt.is_null());
+    if let Some(status) = query.get("status") {
+        if BannerStatus::parse(status).is_none() {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Invalid status filter"));
+        }
+        find = find.filter(banner::Column::Status.eq(status.as_str()));
+    }
+    
+    match find
         .order_by_desc(banner::Column::CreatedAt)
         .limit(limit)
         .all(db.get_ref())
@@ -389,84 +932,235 @@ pub async fn get_banners(
                     .json(ApiResponse::<()>::error("No banners found"));
             }
             
-            let data: Vec<BannerDto> = rows
-                .into_iter()
-                .map(|r| BannerDto {
-                    id: r.id,
-                    title: r.title,
-                    content: r.content,
-                    image_url: r.image_url,
-                    start_date: r.start_date,
-                    end_date: r.end_date,
-                    is_active: r.is_active,
-                    created_at: r.created_at,
-                    updated_at: r.updated_at,
-                })
-                .collect();
+            let mut data: Vec<BannerDto> = Vec::with_capacity(rows.len());
+            for r in rows {
+                data.push(banner_dto(store.get_ref(), r).await);
+            }
+            
+            let chain = locale::fallback_chain(&req, &query, &default_locale);
+            if let Err(e) = localize(db.get_ref(), &mut data, &chain).await {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(&format!("DB error: {}", e)));
+            }
             
-            HttpResponse::Ok().json(ApiResponse::success("Banners fetched", Some(data)))
+            HttpResponse::Ok()
+                .insert_header((header::VARY, "Accept-Language"))
+                .json(ApiResponse::success("Banners fetched", Some(data)))
         }
         Err(e) => HttpResponse::InternalServerError()
             .json(ApiResponse::<()>::error(&format!("DB error: {}", e))),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/banners/active",
+    tag = "banners",
+    summary = "Currently visible banner",
+    description = "Cached with `public, max-age`. When the image URLs are signed or presigned the \
+                   response is `private`, max-age stays below the URL lifetime and the ETag changes \
+                   every half TTL so a 304 never keeps expired URLs.",
+    params(
+        ("locale" = Option<String>, Query, description = "Override Accept-Language, mis. `en`"),
+    ),
+    responses(
+        (status = 200, description = "Active banner, or the default banner when none is visible", body = ApiResponse<BannerDto>),
+        (status = 304, description = "Not modified (If-None-Match / If-Modified-Since)"),
+    )
+)]
 pub async fn get_active_banner(
     db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    cache: web::Data<ActiveBannerCache>,
+    default_locale: web::Data<DefaultLocale>,
+    req: HttpRequest,
+    query: web::Query<HashMap<String, String>>,
 ) -> HttpResponse {
     let now = Utc::now().naive_utc();
     
-    match banner::Entity::find()
-        .filter(banner::Column::IsActive.eq(true))
-        .filter(banner::Column::StartDate.lte(now))
-        .filter(banner::Column::EndDate.gt(now)) // EndDate harus lebih besar dari sekarang (belum expired)
-        .order_by_asc(banner::Column::EndDate) // Prioritas banner yang akan expired lebih dulu
-        .order_by_desc(banner::Column::CreatedAt) // Jika end_date sama, pilih yang terbaru dibuat
-        .one(db.get_ref())
-        .await
-    {
-        Ok(Some(banner)) => {
-            let dto = BannerDto {
-                id: banner.id,
-                title: banner.title,
-                content: banner.content,
-                image_url: banner.image_url,
-                start_date: banner.start_date,
-                end_date: banner.end_date,
-                is_active: banner.is_active,
-                created_at: banner.created_at,
-                updated_at: banner.updated_at,
+    let active = match cache.get_or_load(db.get_ref()).await {
+        Ok(active) => active,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&format!("DB error: {}", e)));
+        }
+    };
+    let cache_control = format!("public, max-age={}", cache.max_age_secs);
+    
+    // Banner dengan jadwal berulang hanya tampil saat jendelanya terbuka
+    let banner = active.candidates.iter().find(|b| {
+        b.start_date <= now && b.end_date > now && schedule::is_visible(b, now)
+    });
+    
+    match banner {
+        Some(banner) => {
+            let chain = locale::fallback_chain(&req, &query, &default_locale);
+            let translation = best_translation(&active.translations, banner.id, &chain);
+            let mut etag = cache::etag(banner, translation);
+            let last_modified = [banner.updated_at, translation.and_then(|t| t.updated_at)]
+                .into_iter()
+                .flatten()
+                .max();
+            
+            // URL gambar bertanda tangan / presigned: jangan disimpan cache bersama, dan salinan
+            // di client tidak boleh dipakai melewati masa berlaku URL-nya
+            let url_ttl = image_url_ttl(store.get_ref(), banner);
+            let mut cache_control = cache_control;
+            if let Some(ttl) = url_ttl {
+                let window = cache::signed_url_window(ttl);
+                etag = cache::etag_for_window(&etag, window);
+                cache_control = format!("private, max-age={}", cache.max_age_secs.min(window));
+            }
+            
+            // If-Modified-Since tidak tahu kapan URL kedaluwarsa, jadi hanya ETag yang dipakai
+            let fresh = cache::is_fresh(&req, &etag, last_modified.filter(|_| url_ttl.is_none()));
+            let mut resp = if fresh {
+                HttpResponse::NotModified()
+            } else {
+                HttpResponse::Ok()
             };
-            HttpResponse::Ok().json(ApiResponse::success("Active banner found", Some(dto)))
+            resp.insert_header((header::ETAG, etag.as_str()))
+                .insert_header((header::CACHE_CONTROL, cache_control))
+                .insert_header((header::VARY, "Accept-Language"));
+            if let Some(modified) = last_modified {
+                resp.insert_header(header::LastModified(cache::http_date(modified)));
+            }
+            if let Some(t) = translation {
+                resp.insert_header((header::CONTENT_LANGUAGE, t.locale.as_str()));
+            }
+            if fresh {
+                return resp.finish();
+            }
+            
+            let mut dto = banner_dto(store.get_ref(), banner.clone()).await;
+            if let Some(t) = translation {
+                apply_translation(&mut dto, t);
+            }
+            resp.json(ApiResponse::success("Active banner found", Some(dto)))
         }
-        Ok(None) => {
+        None => {
+            // Banner default tidak punya versi, cukup ETag lemah yang tetap
+            let etag = "W/\"default\"";
+            if cache::is_fresh(&req, etag, None) {
+                return HttpResponse::NotModified()
+                    .insert_header((header::ETAG, etag))
+                    .insert_header((header::CACHE_CONTROL, cache_control))
+                    .finish();
+            }
+            
             // Return default banner
             let default_banner = BannerDto {
                 id: 0,
                 title: Some("Welcome".to_string()),
                 content: "This is the default banner announcement.".to_string(),
+                content_html: Some("<p>This is the default banner announcement.</p>\n".to_string()),
+                cta_label: None,
+                cta_url: None,
                 image_url: None,
+                image_thumbnail_url: None,
+                image_medium_url: None,
+                image_webp_url: None,
                 start_date: now,
                 end_date: now,
                 is_active: true,
+                status: BannerStatus::Published.as_str().to_string(),
+                recurrence_rule: None,
+                recurrence_duration_minutes: None,
+                recurrence_timezone: None,
                 created_at: Some(now),
                 updated_at: Some(now),
+                deleted_at: None,
+                locale: None,
             };
-            HttpResponse::Ok().json(ApiResponse::success("Default banner", Some(default_banner)))
+            HttpResponse::Ok()
+                .insert_header((header::ETAG, etag))
+                .insert_header((header::CACHE_CONTROL, cache_control))
+                .json(ApiResponse::success("Default banner", Some(default_banner)))
         }
-        Err(e) => HttpResponse::InternalServerError()
-            .json(ApiResponse::<()>::error(&format!("DB error: {}", e))),
     }
 }
 
+// SSE: kirim event setiap kali himpunan banner aktif berubah
+#[utoipa::path(
+    get,
+    path = "/api/banners/stream",
+    tag = "banners",
+    summary = "Server-Sent Events for active banner changes",
+    responses(
+        (status = 200, description = "`text/event-stream` of `active_banners` events; resume with Last-Event-ID", content_type = "text/event-stream", body = String),
+    )
+)]
+pub async fn stream_banners(
+    events: web::Data<BannerEvents>,
+    settings: web::Data<Settings>,
+    shutdown: web::Data<Shutdown>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let last_event_id = req
+        .headers()
+        .get("Last-Event-ID")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok());
+    let (replay, rx) = events.subscribe(last_event_id);
+    let heartbeat = tokio::time::interval(std::time::Duration::from_secs(settings.banner_stream.heartbeat_secs));
+    
+    let state = (VecDeque::from(replay), rx, heartbeat, events.into_inner(), shutdown.into_inner());
+    let stream = futures_util::stream::unfold(state, |(mut pending, mut rx, mut heartbeat, events, shutdown)| async move {
+        let chunk = match pending.pop_front() {
+            Some(event) => event.to_sse(),
+            None => tokio::select! {
+                msg = rx.recv() => match msg {
+                    Ok(event) => event.to_sse(),
+                    // Client tertinggal terlalu jauh, cukup kirim state terbaru
+                    Err(RecvError::Lagged(_)) => events.latest().map(|e| e.to_sse()).unwrap_or_default(),
+                    Err(RecvError::Closed) => return None,
+                },
+                _ = heartbeat.tick() => ": ping\n\n".to_string(),
+                // Stream ditutup saat drain; EventSource reconnect sendiri dengan Last-Event-ID
+                _ = shutdown.draining() => return None,
+            },
+        };
+        Some((
+            Ok::<_, actix_web::Error>(web::Bytes::from(chunk)),
+            (pending, rx, heartbeat, events, shutdown),
+        ))
+    });
+    
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(stream)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/banners/{id}",
+    tag = "banners",
+    summary = "Update a banner",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+    ),
+    request_body = UpdateBannerRequest,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Banner updated", body = ApiResponse<BannerDto>),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 400, description = "Invalid request", body = MessageBody),
+        (status = 422, description = "Validation failed", body = ValidationErrors),
+        (status = 404, description = "Banner not found", body = MessageBody),
+    )
+)]
 pub async fn update_banner(
     db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    actor: Actor,
     path: web::Path<i32>,
-    payload: web::Json<UpdateBannerRequest>,
+    payload: ValidJson<UpdateBannerRequest>,
 ) -> HttpResponse {
     let banner_id = path.into_inner();
     
-    let banner_result = banner::Entity::find_by_id(banner_id).one(db.get_ref()).await;
+    let banner_result = find_live_banner(db.get_ref(), banner_id).await;
     let banner = match banner_result {
         Ok(Some(b)) => b,
         Ok(None) => {
@@ -479,55 +1173,125 @@ pub async fn update_banner(
         }
     };
     
+    // Gambar dari upload terpisah; referensi gambar lama dilepas di transaksi yang sama
+    let new_image = match payload.image_upload_id {
+        Some(upload_id) => match referenced_image(db.get_ref(), upload_id).await {
+            Ok(image) => Some(image),
+            Err(resp) => return resp,
+        },
+        None => None,
+    };
+    let old_keys = media::banner_keys(&banner);
+    
     let mut active: banner::ActiveModel = banner.into();
     
+    if let Some(ref image) = new_image {
+        image.apply(&mut active);
+    }
+    
     if let Some(ref title) = payload.title {
         active.title = Set(Some(title.clone()));
     }
     
     if let Some(ref content) = payload.content {
         active.content = Set(content.clone());
+        active.content_html = Set(Some(markdown::render(content)));
     }
     
-    if let Some(ref start_date_str) = payload.start_date {
-        match chrono::NaiveDateTime::parse_from_str(start_date_str, "%Y-%m-%d %H:%M:%S") {
-            Ok(date) => active.start_date = Set(date),
-            Err(_) => {
-                return HttpResponse::BadRequest()
-                    .json(ApiResponse::<()>::error("Invalid start_date format. Use: YYYY-MM-DD HH:MM:SS"));
-            }
-        }
+    // CTA: "" menghapus, nilai akhir label + URL divalidasi berpasangan
+    if let Some(ref label) = payload.cta_label {
+        active.cta_label = Set(Some(label.trim().to_string()).filter(|l| !l.is_empty()));
     }
     
-    if let Some(ref end_date_str) = payload.end_date {
-        match chrono::NaiveDateTime::parse_from_str(end_date_str, "%Y-%m-%d %H:%M:%S") {
-            Ok(date) => active.end_date = Set(date),
-            Err(_) => {
-                return HttpResponse::BadRequest()
-                    .json(ApiResponse::<()>::error("Invalid end_date format. Use: YYYY-MM-DD HH:MM:SS"));
-            }
+    if let Some(ref url) = payload.cta_url {
+        active.cta_url = Set(Some(url.trim().to_string()).filter(|u| !u.is_empty()));
+    }
+    
+    if let Err(e) = markdown::validate_cta(
+        active.cta_label.as_ref().as_deref(),
+        active.cta_url.as_ref().as_deref(),
+    ) {
+        return e.response();
+    }
+    
+    if let Some(date) = payload.start_date {
+        active.start_date = Set(date);
+    }
+    
+    if let Some(date) = payload.end_date {
+        active.end_date = Set(date);
+        // Diperpanjang: banner.expired dikirim lagi saat end_date baru lewat
+        if date > Utc::now().naive_utc() {
+            active.expiry_notified_at = Set(None);
         }
     }
     
+    // Hanya salah satu tanggal yang dikirim: bandingkan dengan nilai tersimpan
+    if active.start_date.as_ref() >= active.end_date.as_ref() {
+        return match payload.end_date {
+            Some(_) => validation::field_error("end_date", "must be after start_date"),
+            None => validation::field_error("start_date", "must be before end_date"),
+        };
+    }
+    
     if let Some(is_active) = payload.is_active {
         active.is_active = Set(is_active);
     }
     
+    if let Some(ref rule) = payload.recurrence_rule {
+        if rule.trim().is_empty() {
+            active.recurrence_rule = Set(None);
+            active.recurrence_duration_minutes = Set(None);
+            active.recurrence_timezone = Set(None);
+        } else {
+            active.recurrence_rule = Set(Some(rule.clone()));
+        }
+    }
+    
+    if let Some(minutes) = payload.recurrence_duration_minutes {
+        active.recurrence_duration_minutes = Set(Some(minutes));
+    }
+    
+    if let Some(ref tz) = payload.recurrence_timezone {
+        active.recurrence_timezone = Set(Some(tz.clone()).filter(|t| !t.is_empty()));
+    }
+    
+    // Validasi jadwal berulang terhadap nilai akhir banner
+    if let Some(rule) = active.recurrence_rule.as_ref()
+        && let Err(e) = Recurrence::parse(
+            rule,
+            *active.recurrence_duration_minutes.as_ref(),
+            active.recurrence_timezone.as_ref().as_deref(),
+            *active.start_date.as_ref(),
+        )
+    {
+        return e.response();
+    }
+    
+    require_review(&mut active, &actor);
+    
     active.updated_at = Set(Some(Utc::now().naive_utc()));
     
-    match active.update(db.get_ref()).await {
-        Ok(updated) => {
-            let dto = BannerDto {
-                id: updated.id,
-                title: updated.title,
-                content: updated.content,
-                image_url: updated.image_url,
-                start_date: updated.start_date,
-                end_date: updated.end_date,
-                is_active: updated.is_active,
-                created_at: updated.created_at,
-                updated_at: updated.updated_at,
-            };
+    let result: Result<Option<banner::Model>, DbErr> = async {
+        let txn = db.begin().await?;
+        let updated = active.update(&txn).await?;
+        if let Some(ref image) = new_image {
+            let keys = image.keys();
+            if media::acquire(&txn, &keys).await? < keys.len() as u64 {
+                return Ok(None);
+            }
+            media::release(&txn, &old_keys).await?;
+        }
+        revisions::record(&txn, &updated, revisions::UPDATED, Some(&actor)).await?;
+        txn.commit().await?;
+        Ok(Some(updated))
+    }
+    .await;
+    
+    match result {
+        Ok(None) => expired_upload(),
+        Ok(Some(updated)) => {
+            let dto = banner_dto(store.get_ref(), updated).await;
             HttpResponse::Ok().json(ApiResponse::success("Banner updated", Some(dto)))
         }
         Err(e) => HttpResponse::InternalServerError()
@@ -535,21 +1299,1263 @@ pub async fn update_banner(
     }
 }
 
+// Soft delete: banner dipindah ke trash, gambar tetap direferensikan sampai di-purge
+#[utoipa::path(
+    delete,
+    path = "/api/banners/{id}",
+    tag = "banners",
+    summary = "Move a banner to trash",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Banner moved to trash", body = MessageBody),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 404, description = "Banner not found", body = MessageBody),
+    )
+)]
 pub async fn delete_banner(
     db: web::Data<sea_orm::DatabaseConnection>,
+    actor: Actor,
     path: web::Path<i32>,
 ) -> HttpResponse {
     let banner_id = path.into_inner();
     
-    match banner::Entity::delete_by_id(banner_id).exec(db.get_ref()).await {
-        Ok(res) => {
-            if res.rows_affected > 0 {
-                HttpResponse::Ok().json(ApiResponse::<()>::success("Banner deleted", None))
-            } else {
-                HttpResponse::NotFound().json(ApiResponse::<()>::error("Banner not found"))
-            }
+    let banner = match find_live_banner(db.get_ref(), banner_id).await {
+        Ok(Some(b)) => b,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Banner not found"));
         }
-        Err(e) => HttpResponse::InternalServerError()
-            .json(ApiResponse::<()>::error(&format!("Delete error: {}", e))),
-    }
-}
\ No newline at end of file
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&format!("DB error: {}", e)));
+        }
+    };
+    
+    let now = Utc::now().naive_utc();
+    let mut active: banner::ActiveModel = banner.into();
+    active.deleted_at = Set(Some(now));
+    active.updated_at = Set(Some(now));
+    
+    let result: Result<banner::Model, DbErr> = async {
+        let txn = db.begin().await?;
+        let deleted = active.update(&txn).await?;
+        revisions::record(&txn, &deleted, revisions::DELETED, Some(&actor)).await?;
+        txn.commit().await?;
+        Ok(deleted)
+    }
+    .await;
+    
+    match result {
+        Ok(_) => HttpResponse::Ok().json(ApiResponse::<()>::success("Banner moved to trash", None)),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("Delete error: {}", e))),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/banners/trash",
+    tag = "banners",
+    summary = "List trashed banners",
+    params(
+        ("limit" = Option<u64>, Query, description = "Default 50"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Vec<BannerDto>>),
+    )
+)]
+pub async fn get_trash(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    query: web::Query<HashMap<String, String>>,
+) -> HttpResponse {
+    let limit = query
+        .get("limit")
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(50);
+    
+    match banner::Entity::find()
+        .filter(banner::Column::DeletedAt.is_not_null())
+        .order_by_desc(banner::Column::DeletedAt)
+        .limit(limit)
+        .all(db.get_ref())
+        .await
+    {
+        Ok(rows) => {
+            let mut data: Vec<BannerDto> = Vec::with_capacity(rows.len());
+            for r in rows {
+                data.push(banner_dto(store.get_ref(), r).await);
+            }
+            HttpResponse::Ok().json(ApiResponse::success("Trash fetched", Some(data)))
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("DB error: {}", e))),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/banners/{id}/restore",
+    tag = "banners",
+    summary = "Restore a banner from trash",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Banner restored", body = ApiResponse<BannerDto>),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 404, description = "Banner not found", body = MessageBody),
+    )
+)]
+pub async fn restore_banner(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    actor: Actor,
+    path: web::Path<i32>,
+) -> HttpResponse {
+    let banner_id = path.into_inner();
+    
+    let banner = match banner::Entity::find_by_id(banner_id)
+        .filter(banner::Column::DeletedAt.is_not_null())
+        .one(db.get_ref())
+        .await
+    {
+        Ok(Some(b)) => b,
+        Ok(None) => {
+            return HttpResponse::NotFound()
+                .json(ApiResponse::<()>::error("Banner not found in trash"));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&format!("DB error: {}", e)));
+        }
+    };
+    
+    let mut active: banner::ActiveModel = banner.into();
+    active.deleted_at = Set(None);
+    active.updated_at = Set(Some(Utc::now().naive_utc()));
+    
+    let result: Result<banner::Model, DbErr> = async {
+        let txn = db.begin().await?;
+        let restored = active.update(&txn).await?;
+        revisions::record(&txn, &restored, revisions::RESTORED, Some(&actor)).await?;
+        txn.commit().await?;
+        Ok(restored)
+    }
+    .await;
+    
+    match result {
+        Ok(updated) => {
+            let dto = banner_dto(store.get_ref(), updated).await;
+            HttpResponse::Ok().json(ApiResponse::success("Banner restored", Some(dto)))
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("Update error: {}", e))),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/banners/{id}/revisions",
+    tag = "revisions",
+    summary = "List banner revisions",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Vec<BannerRevisionDto>>),
+    )
+)]
+pub async fn get_banner_revisions(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    path: web::Path<i32>,
+) -> HttpResponse {
+    let banner_id = path.into_inner();
+    
+    match banner_revision::Entity::find()
+        .filter(banner_revision::Column::BannerId.eq(banner_id))
+        .order_by_desc(banner_revision::Column::Revision)
+        .all(db.get_ref())
+        .await
+    {
+        Ok(rows) => {
+            if rows.is_empty() {
+                return HttpResponse::NotFound()
+                    .json(ApiResponse::<()>::error("No revisions found for this banner"));
+            }
+            let data: Vec<BannerRevisionDto> = rows.into_iter().map(BannerRevisionDto::from).collect();
+            HttpResponse::Ok().json(ApiResponse::success("Revisions fetched", Some(data)))
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("DB error: {}", e))),
+    }
+}
+
+// Rollback banner ke isi revisi tertentu (dicatat sebagai revisi baru)
+#[utoipa::path(
+    post,
+    path = "/api/banners/{id}/revisions/{rev}/restore",
+    tag = "revisions",
+    summary = "Roll a banner back to a revision",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+        ("rev" = i32, Path, description = "Revision number"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Banner restored to revision", body = ApiResponse<BannerDto>),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 400, description = "The revision's recurrence schedule is no longer valid", body = MessageBody),
+        (status = 404, description = "Banner not found", body = MessageBody),
+    )
+)]
+pub async fn restore_banner_revision(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    actor: Actor,
+    path: web::Path<(i32, i32)>,
+) -> HttpResponse {
+    let (banner_id, revision) = path.into_inner();
+    
+    let banner = match find_live_banner(db.get_ref(), banner_id).await {
+        Ok(Some(b)) => b,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Banner not found"));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&format!("DB error: {}", e)));
+        }
+    };
+    
+    let rev = match banner_revision::Entity::find()
+        .filter(banner_revision::Column::BannerId.eq(banner_id))
+        .filter(banner_revision::Column::Revision.eq(revision))
+        .one(db.get_ref())
+        .await
+    {
+        Ok(Some(r)) => r,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Revision not found"));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&format!("DB error: {}", e)));
+        }
+    };
+    
+    let snapshot = match revisions::snapshot(&rev) {
+        Ok(s) => s,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&format!("Invalid revision snapshot: {}", e)));
+        }
+    };
+    
+    // Jadwal berulang ikut di-rollback, divalidasi ulang seperti di update_banner
+    if let Some(rule) = snapshot.recurrence_rule.as_deref()
+        && let Err(e) = Recurrence::parse(
+            rule,
+            snapshot.recurrence_duration_minutes,
+            snapshot.recurrence_timezone.as_deref(),
+            snapshot.start_date,
+        )
+    {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error(&format!("Revision schedule is no longer valid: {}", e)));
+    }
+    
+    let result: Result<banner::Model, DbErr> = async {
+        let old_keys = media::banner_keys(&banner);
+        let new_keys = snapshot.image_keys();
+        let txn = db.begin().await?;
+        
+        let mut active: banner::ActiveModel = banner.into();
+        active.title = Set(snapshot.title.clone());
+        active.content = Set(snapshot.content.clone());
+        active.content_html = Set(Some(markdown::render(&snapshot.content)));
+        active.cta_label = Set(snapshot.cta_label.clone());
+        active.cta_url = Set(snapshot.cta_url.clone());
+        active.start_date = Set(snapshot.start_date);
+        active.end_date = Set(snapshot.end_date);
+        if snapshot.end_date > Utc::now().naive_utc() {
+            active.expiry_notified_at = Set(None);
+        }
+        active.is_active = Set(snapshot.is_active);
+        active.recurrence_rule = Set(snapshot.recurrence_rule.clone());
+        active.recurrence_duration_minutes = Set(snapshot.recurrence_duration_minutes);
+        active.recurrence_timezone = Set(snapshot.recurrence_timezone.clone());
+        require_review(&mut active, &actor);
+        
+        // Gambar lama hanya dipasang lagi kalau file-nya belum dibersihkan GC
+        if old_keys != new_keys && media::all_tracked(&txn, &new_keys).await? {
+            active.image_url = Set(snapshot.image_url.clone());
+            active.image_thumbnail_url = Set(snapshot.image_thumbnail_url.clone());
+            active.image_medium_url = Set(snapshot.image_medium_url.clone());
+            active.image_webp_url = Set(snapshot.image_webp_url.clone());
+            media::acquire(&txn, &new_keys).await?;
+            media::release(&txn, &old_keys).await?;
+        }
+        
+        active.updated_at = Set(Some(Utc::now().naive_utc()));
+        let updated = active.update(&txn).await?;
+        revisions::record(&txn, &updated, revisions::ROLLED_BACK, Some(&actor)).await?;
+        txn.commit().await?;
+        Ok(updated)
+    }
+    .await;
+    
+    match result {
+        Ok(updated) => {
+            let dto = banner_dto(store.get_ref(), updated).await;
+            HttpResponse::Ok().json(ApiResponse::success("Banner restored to revision", Some(dto)))
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("Update error: {}", e))),
+    }
+}
+
+// Translation handlers
+
+#[utoipa::path(
+    get,
+    path = "/api/banners/{id}/translations",
+    tag = "translations",
+    summary = "List banner translations",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Vec<BannerTranslationDto>>),
+    )
+)]
+pub async fn get_banner_translations(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    path: web::Path<i32>,
+) -> HttpResponse {
+    let banner_id = path.into_inner();
+    
+    match banner_translation::Entity::find()
+        .filter(banner_translation::Column::BannerId.eq(banner_id))
+        .order_by_asc(banner_translation::Column::Locale)
+        .all(db.get_ref())
+        .await
+    {
+        Ok(rows) => {
+            let data: Vec<BannerTranslationDto> =
+                rows.into_iter().map(BannerTranslationDto::from).collect();
+            HttpResponse::Ok().json(ApiResponse::success("Translations fetched", Some(data)))
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("DB error: {}", e))),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/banners/{id}/translations/{locale}",
+    tag = "translations",
+    summary = "Create or replace a translation",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+        ("locale" = String, Path, description = "Language tag, mis. `en` atau `en-us`"),
+    ),
+    request_body = UpsertTranslationRequest,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Translation saved", body = ApiResponse<BannerTranslationDto>),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 400, description = "Invalid locale", body = MessageBody),
+        (status = 404, description = "Banner not found", body = MessageBody),
+        (status = 422, description = "Validation failed", body = ValidationErrors),
+    )
+)]
+pub async fn upsert_banner_translation(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    actor: Actor,
+    path: web::Path<(i32, String)>,
+    payload: ValidJson<UpsertTranslationRequest>,
+) -> HttpResponse {
+    let (banner_id, locale_tag) = path.into_inner();
+    let locale_tag = locale::normalize(&locale_tag);
+    if !locale::is_valid(&locale_tag) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Invalid locale"));
+    }
+    
+    let banner = match find_live_banner(db.get_ref(), banner_id).await {
+        Ok(Some(b)) => b,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Banner not found"));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&format!("DB error: {}", e)));
+        }
+    };
+    
+    let now = Utc::now().naive_utc();
+    let content_html = markdown::render(&payload.content);
+    let result: Result<banner_translation::Model, DbErr> = async {
+        let txn = db.begin().await?;
+        let existing = banner_translation::Entity::find()
+            .filter(banner_translation::Column::BannerId.eq(banner_id))
+            .filter(banner_translation::Column::Locale.eq(locale_tag.as_str()))
+            .one(&txn)
+            .await?;
+        
+        let saved = match existing {
+            Some(t) => {
+                let mut active: banner_translation::ActiveModel = t.into();
+                active.title = Set(payload.title.clone());
+                active.content = Set(payload.content.clone());
+                active.content_html = Set(Some(content_html.clone()));
+                active.updated_at = Set(Some(now));
+                active.update(&txn).await?
+            }
+            None => {
+                banner_translation::ActiveModel {
+                    banner_id: Set(banner_id),
+                    locale: Set(locale_tag.clone()),
+                    title: Set(payload.title.clone()),
+                    content: Set(payload.content.clone()),
+                    content_html: Set(Some(content_html.clone())),
+                    created_at: Set(Some(now)),
+                    updated_at: Set(Some(now)),
+                    ..Default::default()
+                }
+                .insert(&txn)
+                .await?
+            }
+        };
+        
+        require_review_after_edit(&txn, banner, &actor).await?;
+        txn.commit().await?;
+        Ok(saved)
+    }
+    .await;
+    
+    match result {
+        Ok(saved) => HttpResponse::Ok().json(ApiResponse::success(
+            "Translation saved",
+            Some(BannerTranslationDto::from(saved)),
+        )),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("Update error: {}", e))),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/banners/{id}/translations/{locale}",
+    tag = "translations",
+    summary = "Delete a translation",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+        ("locale" = String, Path, description = "Language tag"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Translation deleted", body = MessageBody),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 404, description = "Translation not found", body = MessageBody),
+    )
+)]
+pub async fn delete_banner_translation(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    actor: Actor,
+    path: web::Path<(i32, String)>,
+) -> HttpResponse {
+    let (banner_id, locale_tag) = path.into_inner();
+    let locale_tag = locale::normalize(&locale_tag);
+    
+    let banner = match find_live_banner(db.get_ref(), banner_id).await {
+        Ok(Some(b)) => b,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Banner not found"));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&format!("DB error: {}", e)));
+        }
+    };
+    
+    let result: Result<u64, DbErr> = async {
+        let txn = db.begin().await?;
+        let res = banner_translation::Entity::delete_many()
+            .filter(banner_translation::Column::BannerId.eq(banner_id))
+            .filter(banner_translation::Column::Locale.eq(locale_tag.as_str()))
+            .exec(&txn)
+            .await?;
+        if res.rows_affected > 0 {
+            require_review_after_edit(&txn, banner, &actor).await?;
+        }
+        txn.commit().await?;
+        Ok(res.rows_affected)
+    }
+    .await;
+    
+    match result {
+        Ok(rows) if rows > 0 => {
+            HttpResponse::Ok().json(ApiResponse::<()>::success("Translation deleted", None))
+        }
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()>::error("Translation not found")),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("Delete error: {}", e))),
+    }
+}
+
+// Perubahan konten, gambar atau rollback oleh non-reviewer harus direview ulang sebelum tayang
+fn require_review(active: &mut banner::ActiveModel, actor: &Actor) {
+    if !actor.can_review() {
+        active.status = Set(BannerStatus::Draft.as_str().to_string());
+    }
+}
+
+// Untuk perubahan di tabel lain (terjemahan): banner ikut kembali ke draft
+async fn require_review_after_edit<C: ConnectionTrait>(
+    db: &C,
+    banner: banner::Model,
+    actor: &Actor,
+) -> Result<(), DbErr> {
+    if actor.can_review() || banner.status == BannerStatus::Draft.as_str() {
+        return Ok(());
+    }
+    let mut active: banner::ActiveModel = banner.into();
+    require_review(&mut active, actor);
+    active.updated_at = Set(Some(Utc::now().naive_utc()));
+    let updated = active.update(db).await?;
+    revisions::record(db, &updated, revisions::UPDATED, Some(actor)).await?;
+    Ok(())
+}
+
+// Workflow handlers
+
+async fn transition_banner(
+    db: &sea_orm::DatabaseConnection,
+    store: &dyn BlobStore,
+    actor: Actor,
+    banner_id: i32,
+    transition: Transition,
+    comment: Option<String>,
+) -> HttpResponse {
+    let banner = match find_live_banner(db, banner_id).await {
+        Ok(Some(b)) => b,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Banner not found"));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&format!("DB error: {}", e)));
+        }
+    };
+    
+    let from = BannerStatus::parse(&banner.status).unwrap_or(BannerStatus::Draft);
+    let to = match transition.target(from) {
+        Some(to) => to,
+        None => {
+            return HttpResponse::Conflict().json(ApiResponse::<()>::error(&format!(
+                "Cannot {} a banner with status {}",
+                transition.verb(),
+                from
+            )));
+        }
+    };
+    
+    if transition.requires_reviewer() && !actor.can_review() {
+        return auth::forbidden("Reviewer or admin role required");
+    }
+    
+    let comment = comment.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+    if transition == Transition::Reject && comment.is_none() {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("A comment is required when rejecting a banner"));
+    }
+    
+    // Reviewer tidak boleh meng-approve submission miliknya sendiri (kecuali admin)
+    if transition == Transition::Approve && !actor.is_admin() {
+        match banner_review::Entity::find()
+            .filter(banner_review::Column::BannerId.eq(banner.id))
+            .filter(banner_review::Column::ToStatus.eq(BannerStatus::PendingReview.as_str()))
+            .order_by_desc(banner_review::Column::Id)
+            .one(db)
+            .await
+        {
+            Ok(Some(submission)) if submission.actor == actor.user_id => {
+                return auth::forbidden("You cannot approve your own submission");
+            }
+            Ok(_) => {}
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(&format!("DB error: {}", e)));
+            }
+        }
+    }
+    
+    let now = Utc::now().naive_utc();
+    let mut active: banner::ActiveModel = banner.into();
+    active.status = Set(to.as_str().to_string());
+    active.updated_at = Set(Some(now));
+    
+    let result: Result<banner::Model, DbErr> = async {
+        let txn = db.begin().await?;
+        let updated = active.update(&txn).await?;
+        banner_review::ActiveModel {
+            banner_id: Set(updated.id),
+            from_status: Set(from.as_str().to_string()),
+            to_status: Set(to.as_str().to_string()),
+            actor: Set(actor.user_id.clone()),
+            comment: Set(comment),
+            created_at: Set(Some(now)),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        revisions::record(&txn, &updated, transition.revision_action(), Some(&actor)).await?;
+        if to == BannerStatus::Published {
+            webhooks::enqueue(&txn, webhooks::BANNER_PUBLISHED, webhooks::banner_data(&updated))
+                .await?;
+        }
+        txn.commit().await?;
+        Ok(updated)
+    }
+    .await;
+    
+    match result {
+        Ok(updated) => {
+            let dto = banner_dto(store, updated).await;
+            HttpResponse::Ok().json(ApiResponse::success(&format!("Banner {}", to), Some(dto)))
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("Update error: {}", e))),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/banners/{id}/submit",
+    tag = "banner workflow",
+    summary = "Submit for review a banner",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+    ),
+    request_body = Option<TransitionRequest>,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Banner status changed", body = ApiResponse<BannerDto>),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 404, description = "Banner not found", body = MessageBody),
+        (status = 409, description = "Transition not allowed from the current status", body = MessageBody),
+    )
+)]
+pub async fn submit_banner(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    actor: Actor,
+    path: web::Path<i32>,
+    payload: Option<web::Json<TransitionRequest>>,
+) -> HttpResponse {
+    let comment = payload.and_then(|p| p.into_inner().comment);
+    transition_banner(db.get_ref(), store.get_ref(), actor, path.into_inner(), Transition::Submit, comment).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/banners/{id}/approve",
+    tag = "banner workflow",
+    summary = "Approve a banner",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+    ),
+    request_body = Option<TransitionRequest>,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Banner status changed", body = ApiResponse<BannerDto>),
+        (status = 403, description = "Role not allowed", body = MessageBody),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 404, description = "Banner not found", body = MessageBody),
+        (status = 409, description = "Transition not allowed from the current status", body = MessageBody),
+    )
+)]
+pub async fn approve_banner(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    actor: Actor,
+    path: web::Path<i32>,
+    payload: Option<web::Json<TransitionRequest>>,
+) -> HttpResponse {
+    let comment = payload.and_then(|p| p.into_inner().comment);
+    transition_banner(db.get_ref(), store.get_ref(), actor, path.into_inner(), Transition::Approve, comment).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/banners/{id}/reject",
+    tag = "banner workflow",
+    summary = "Reject a banner",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+    ),
+    request_body = Option<TransitionRequest>,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Banner status changed", body = ApiResponse<BannerDto>),
+        (status = 400, description = "Invalid request", body = MessageBody),
+        (status = 403, description = "Role not allowed", body = MessageBody),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 404, description = "Banner not found", body = MessageBody),
+        (status = 409, description = "Transition not allowed from the current status", body = MessageBody),
+    )
+)]
+pub async fn reject_banner(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    actor: Actor,
+    path: web::Path<i32>,
+    payload: Option<web::Json<TransitionRequest>>,
+) -> HttpResponse {
+    let comment = payload.and_then(|p| p.into_inner().comment);
+    transition_banner(db.get_ref(), store.get_ref(), actor, path.into_inner(), Transition::Reject, comment).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/banners/{id}/publish",
+    tag = "banner workflow",
+    summary = "Publish a banner",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+    ),
+    request_body = Option<TransitionRequest>,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Banner status changed", body = ApiResponse<BannerDto>),
+        (status = 403, description = "Role not allowed", body = MessageBody),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 404, description = "Banner not found", body = MessageBody),
+        (status = 409, description = "Transition not allowed from the current status", body = MessageBody),
+    )
+)]
+pub async fn publish_banner(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    actor: Actor,
+    path: web::Path<i32>,
+    payload: Option<web::Json<TransitionRequest>>,
+) -> HttpResponse {
+    let comment = payload.and_then(|p| p.into_inner().comment);
+    transition_banner(db.get_ref(), store.get_ref(), actor, path.into_inner(), Transition::Publish, comment).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/banners/{id}/archive",
+    tag = "banner workflow",
+    summary = "Archive a banner",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+    ),
+    request_body = Option<TransitionRequest>,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Banner status changed", body = ApiResponse<BannerDto>),
+        (status = 403, description = "Role not allowed", body = MessageBody),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 404, description = "Banner not found", body = MessageBody),
+        (status = 409, description = "Transition not allowed from the current status", body = MessageBody),
+    )
+)]
+pub async fn archive_banner(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    actor: Actor,
+    path: web::Path<i32>,
+    payload: Option<web::Json<TransitionRequest>>,
+) -> HttpResponse {
+    let comment = payload.and_then(|p| p.into_inner().comment);
+    transition_banner(db.get_ref(), store.get_ref(), actor, path.into_inner(), Transition::Archive, comment).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/banners/{id}/reopen",
+    tag = "banner workflow",
+    summary = "Reopen a banner",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+    ),
+    request_body = Option<TransitionRequest>,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Banner status changed", body = ApiResponse<BannerDto>),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 404, description = "Banner not found", body = MessageBody),
+        (status = 409, description = "Transition not allowed from the current status", body = MessageBody),
+    )
+)]
+pub async fn reopen_banner(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    actor: Actor,
+    path: web::Path<i32>,
+    payload: Option<web::Json<TransitionRequest>>,
+) -> HttpResponse {
+    let comment = payload.and_then(|p| p.into_inner().comment);
+    transition_banner(db.get_ref(), store.get_ref(), actor, path.into_inner(), Transition::Reopen, comment).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/banners/{id}/reviews",
+    tag = "banner workflow",
+    summary = "Review history of a banner",
+    params(
+        ("id" = i32, Path, description = "Banner id"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Vec<BannerReviewDto>>),
+    )
+)]
+pub async fn get_banner_reviews(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    path: web::Path<i32>,
+) -> HttpResponse {
+    let banner_id = path.into_inner();
+    
+    match banner_review::Entity::find()
+        .filter(banner_review::Column::BannerId.eq(banner_id))
+        .order_by_desc(banner_review::Column::Id)
+        .all(db.get_ref())
+        .await
+    {
+        Ok(rows) => {
+            let data: Vec<BannerReviewDto> = rows.into_iter().map(BannerReviewDto::from).collect();
+            HttpResponse::Ok().json(ApiResponse::success("Reviews fetched", Some(data)))
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("DB error: {}", e))),
+    }
+}
+
+// Admin handlers
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/media/gc",
+    tag = "admin",
+    summary = "Sweep unreferenced uploads",
+    params(
+        ("dry_run" = Option<bool>, Query, description = "Only report what would be deleted"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<SweepReport>),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 403, description = "Role not allowed", body = MessageBody),
+    )
+)]
+pub async fn run_media_gc(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    store: web::Data<dyn BlobStore>,
+    settings: web::Data<Settings>,
+    actor: Actor,
+    query: web::Query<HashMap<String, String>>,
+) -> HttpResponse {
+    if !actor.is_admin() {
+        return auth::forbidden("Admin role required");
+    }
+    
+    let dry_run = query
+        .get("dry_run")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+    
+    match media::sweep(db.get_ref(), store.get_ref(), chrono::Duration::seconds(settings.media_gc.grace_secs), dry_run).await {
+        Ok(report) => {
+            let message = if dry_run { "Media GC dry run" } else { "Media GC completed" };
+            HttpResponse::Ok().json(ApiResponse::success(message, Some(report)))
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("DB error: {}", e))),
+    }
+}
+
+// Webhook handlers (admin)
+
+#[utoipa::path(
+    post,
+    path = "/api/webhooks",
+    tag = "webhooks",
+    summary = "Subscribe a webhook",
+    request_body = CreateWebhookRequest,
+    security(("bearer" = [])),
+    responses(
+        (status = 201, description = "Webhook created; the secret is only returned here", body = ApiResponse<WebhookDto>),
+        (status = 400, description = "Invalid request", body = MessageBody),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 403, description = "Role not allowed", body = MessageBody),
+    )
+)]
+pub async fn create_webhook(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    actor: Actor,
+    payload: web::Json<CreateWebhookRequest>,
+) -> HttpResponse {
+    if !actor.is_admin() {
+        return auth::forbidden("Admin role required");
+    }
+    
+    let url = payload.url.trim().to_string();
+    if let Err(e) = webhooks::validate(&url, &payload.events) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(&e));
+    }
+    
+    let secret = match payload.secret.as_deref().map(str::trim) {
+        Some(s) if s.len() < 16 => {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("secret must be at least 16 characters"));
+        }
+        Some(s) => s.to_string(),
+        None => format!("whsec_{}", Uuid::new_v4().simple()),
+    };
+    
+    let now = Utc::now().naive_utc();
+    let model = webhook_subscription::ActiveModel {
+        url: Set(url),
+        secret: Set(secret.clone()),
+        events: Set(payload.events.join(",")),
+        is_active: Set(true),
+        created_by: Set(Some(actor.user_id.clone())),
+        created_at: Set(Some(now)),
+        updated_at: Set(Some(now)),
+        ..Default::default()
+    };
+    
+    match model.insert(db.get_ref()).await {
+        Ok(inserted) => {
+            let mut dto = WebhookDto::from(inserted);
+            dto.secret = Some(secret);
+            HttpResponse::Created().json(ApiResponse::success("Webhook created", Some(dto)))
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("Insert error: {}", e))),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/webhooks",
+    tag = "webhooks",
+    summary = "List webhooks",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Vec<WebhookDto>>),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 403, description = "Role not allowed", body = MessageBody),
+    )
+)]
+pub async fn get_webhooks(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    actor: Actor,
+) -> HttpResponse {
+    if !actor.is_admin() {
+        return auth::forbidden("Admin role required");
+    }
+    
+    match webhook_subscription::Entity::find()
+        .order_by_asc(webhook_subscription::Column::Id)
+        .all(db.get_ref())
+        .await
+    {
+        Ok(rows) => {
+            let data: Vec<WebhookDto> = rows.into_iter().map(WebhookDto::from).collect();
+            HttpResponse::Ok().json(ApiResponse::success("Webhooks fetched", Some(data)))
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("DB error: {}", e))),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/webhooks/{id}",
+    tag = "webhooks",
+    summary = "Update a webhook",
+    params(
+        ("id" = i32, Path, description = "Webhook id"),
+    ),
+    request_body = UpdateWebhookRequest,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Webhook updated", body = ApiResponse<WebhookDto>),
+        (status = 400, description = "Invalid request", body = MessageBody),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 403, description = "Role not allowed", body = MessageBody),
+        (status = 404, description = "Webhook not found", body = MessageBody),
+    )
+)]
+pub async fn update_webhook(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    actor: Actor,
+    path: web::Path<i32>,
+    payload: web::Json<UpdateWebhookRequest>,
+) -> HttpResponse {
+    if !actor.is_admin() {
+        return auth::forbidden("Admin role required");
+    }
+    
+    let webhook = match webhook_subscription::Entity::find_by_id(path.into_inner())
+        .one(db.get_ref())
+        .await
+    {
+        Ok(Some(w)) => w,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Webhook not found"));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&format!("DB error: {}", e)));
+        }
+    };
+    
+    let url = payload
+        .url
+        .as_deref()
+        .map(|u| u.trim().to_string())
+        .unwrap_or_else(|| webhook.url.clone());
+    let events = payload
+        .events
+        .clone()
+        .unwrap_or_else(|| webhook.events.split(',').map(|e| e.trim().to_string()).collect());
+    if let Err(e) = webhooks::validate(&url, &events) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(&e));
+    }
+    
+    let mut active: webhook_subscription::ActiveModel = webhook.into();
+    active.url = Set(url);
+    active.events = Set(events.join(","));
+    if let Some(is_active) = payload.is_active {
+        active.is_active = Set(is_active);
+    }
+    active.updated_at = Set(Some(Utc::now().naive_utc()));
+    
+    match active.update(db.get_ref()).await {
+        Ok(updated) => HttpResponse::Ok()
+            .json(ApiResponse::success("Webhook updated", Some(WebhookDto::from(updated)))),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("Update error: {}", e))),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{id}",
+    tag = "webhooks",
+    summary = "Delete a webhook",
+    params(
+        ("id" = i32, Path, description = "Webhook id"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Webhook deleted", body = MessageBody),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 403, description = "Role not allowed", body = MessageBody),
+        (status = 404, description = "Webhook not found", body = MessageBody),
+    )
+)]
+pub async fn delete_webhook(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    actor: Actor,
+    path: web::Path<i32>,
+) -> HttpResponse {
+    if !actor.is_admin() {
+        return auth::forbidden("Admin role required");
+    }
+    
+    // Riwayat pengiriman tetap disimpan; yang masih pending akan ditandai failed
+    match webhook_subscription::Entity::delete_by_id(path.into_inner())
+        .exec(db.get_ref())
+        .await
+    {
+        Ok(res) if res.rows_affected == 0 => {
+            HttpResponse::NotFound().json(ApiResponse::<()>::error("Webhook not found"))
+        }
+        Ok(_) => HttpResponse::Ok().json(ApiResponse::<()>::success("Webhook deleted", None)),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("Delete error: {}", e))),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/{id}/ping",
+    tag = "webhooks",
+    summary = "Queue a webhook.ping delivery",
+    params(
+        ("id" = i32, Path, description = "Webhook id"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 202, description = "Ping queued", body = ApiResponse<WebhookDeliveryDto>),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 403, description = "Role not allowed", body = MessageBody),
+        (status = 404, description = "Webhook not found", body = MessageBody),
+    )
+)]
+pub async fn ping_webhook(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    actor: Actor,
+    path: web::Path<i32>,
+) -> HttpResponse {
+    if !actor.is_admin() {
+        return auth::forbidden("Admin role required");
+    }
+    let webhook_id = path.into_inner();
+    
+    match webhook_subscription::Entity::find_by_id(webhook_id).one(db.get_ref()).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Webhook not found"));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&format!("DB error: {}", e)));
+        }
+    }
+    
+    let data = serde_json::json!({ "subscription_id": webhook_id });
+    match webhooks::enqueue_for(db.get_ref(), webhook_id, webhooks::PING, data).await {
+        Ok(delivery) => HttpResponse::Accepted().json(ApiResponse::success(
+            "Ping queued",
+            Some(WebhookDeliveryDto::from(delivery)),
+        )),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("Insert error: {}", e))),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{id}/deliveries",
+    tag = "webhooks",
+    summary = "Delivery log of a webhook",
+    params(
+        ("id" = i32, Path, description = "Webhook id"),
+        ("status" = Option<String>, Query, description = "pending, delivered or failed"),
+        ("limit" = Option<u64>, Query, description = "Default 50"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "OK", body = ApiResponse<Vec<WebhookDeliveryDto>>),
+        (status = 400, description = "Invalid request", body = MessageBody),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 403, description = "Role not allowed", body = MessageBody),
+    )
+)]
+pub async fn get_webhook_deliveries(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    actor: Actor,
+    path: web::Path<i32>,
+    query: web::Query<HashMap<String, String>>,
+) -> HttpResponse {
+    if !actor.is_admin() {
+        return auth::forbidden("Admin role required");
+    }
+    
+    let limit = query
+        .get("limit")
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(50);
+    
+    let mut find = webhook_delivery::Entity::find()
+        .filter(webhook_delivery::Column::SubscriptionId.eq(path.into_inner()));
+    if let Some(status) = query.get("status") {
+        if ![webhooks::PENDING, webhooks::DELIVERED, webhooks::FAILED].contains(&status.as_str()) {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Invalid status filter"));
+        }
+        find = find.filter(webhook_delivery::Column::Status.eq(status.as_str()));
+    }
+    
+    match find
+        .order_by_desc(webhook_delivery::Column::Id)
+        .limit(limit)
+        .all(db.get_ref())
+        .await
+    {
+        Ok(rows) => {
+            let data: Vec<WebhookDeliveryDto> =
+                rows.into_iter().map(WebhookDeliveryDto::from).collect();
+            HttpResponse::Ok().json(ApiResponse::success("Deliveries fetched", Some(data)))
+        }
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("DB error: {}", e))),
+    }
+}
+
+// Kirim ulang delivery yang sudah failed, jatah retry dihitung dari awal
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/{id}/deliveries/{delivery_id}/retry",
+    tag = "webhooks",
+    summary = "Retry a failed delivery",
+    params(
+        ("id" = i32, Path, description = "Webhook id"),
+        ("delivery_id" = i32, Path, description = "Delivery id"),
+    ),
+    security(("bearer" = [])),
+    responses(
+        (status = 202, description = "Delivery queued for retry", body = ApiResponse<WebhookDeliveryDto>),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 403, description = "Role not allowed", body = MessageBody),
+        (status = 404, description = "Delivery not found", body = MessageBody),
+        (status = 409, description = "Delivery has not failed", body = MessageBody),
+    )
+)]
+pub async fn retry_webhook_delivery(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    actor: Actor,
+    path: web::Path<(i32, i32)>,
+) -> HttpResponse {
+    if !actor.is_admin() {
+        return auth::forbidden("Admin role required");
+    }
+    let (webhook_id, delivery_id) = path.into_inner();
+    
+    let delivery = match webhook_delivery::Entity::find_by_id(delivery_id)
+        .filter(webhook_delivery::Column::SubscriptionId.eq(webhook_id))
+        .one(db.get_ref())
+        .await
+    {
+        Ok(Some(d)) => d,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Delivery not found"));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&format!("DB error: {}", e)));
+        }
+    };
+    
+    if delivery.status != webhooks::FAILED {
+        return HttpResponse::Conflict()
+            .json(ApiResponse::<()>::error("Only failed deliveries can be retried"));
+    }
+    
+    let now = Utc::now().naive_utc();
+    let mut active: webhook_delivery::ActiveModel = delivery.into();
+    active.status = Set(webhooks::PENDING.to_string());
+    active.attempts = Set(0);
+    active.next_attempt_at = Set(Some(now));
+    active.updated_at = Set(Some(now));
+    
+    match active.update(db.get_ref()).await {
+        Ok(updated) => HttpResponse::Accepted().json(ApiResponse::success(
+            "Delivery queued for retry",
+            Some(WebhookDeliveryDto::from(updated)),
+        )),
+        Err(e) => HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error(&format!("Update error: {}", e))),
+    }
+}
@@ -0,0 +1,195 @@
+use actix_web::{HttpResponse, web};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::Settings;
+use crate::openapi::MessageBody;
+use crate::responses::ApiResponse;
+use crate::shutdown::Shutdown;
+
+// Batas waktu per cek, supaya probe tidak menggantung saat DB mati
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+const OK: &str = "ok";
+const FAIL: &str = "fail";
+const SKIPPED: &str = "skipped";
+
+#[derive(Serialize, ToSchema)]
+pub struct CheckResult {
+    #[schema(example = "ok")]
+    pub status: String, // ok, fail atau skipped
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending: Option<Vec<String>>, // nama migrasi yang belum dijalankan
+}
+
+impl CheckResult {
+    fn skipped(reason: &str) -> Self {
+        Self {
+            status: SKIPPED.to_string(),
+            latency_ms: 0,
+            error: Some(reason.to_string()),
+            pending: None,
+        }
+    }
+
+    fn failed(&self) -> bool {
+        self.status == FAIL
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReadinessReport {
+    pub database: CheckResult,
+    pub upload_dir: CheckResult,
+    pub migrations: CheckResult,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct VersionInfo {
+    #[schema(example = "rest_api")]
+    pub name: String,
+    #[schema(example = "0.1.0")]
+    pub version: String,
+    pub git_sha: Option<String>,    // env GIT_SHA saat build
+    pub build_time: Option<String>, // env BUILD_TIME saat build
+    #[schema(example = "release")]
+    pub profile: String,
+}
+
+// Jalankan satu cek dengan timeout dan catat durasinya
+async fn run_check<F>(check: F) -> CheckResult
+where
+    F: Future<Output = Result<Option<Vec<String>>, String>>,
+{
+    let started = Instant::now();
+    let result = tokio::time::timeout(CHECK_TIMEOUT, check).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let (status, error, pending) = match result {
+        Ok(Ok(pending)) => (OK, None, pending),
+        Ok(Err(e)) => (FAIL, Some(e), None),
+        Err(_) => (FAIL, Some(format!("timed out after {}s", CHECK_TIMEOUT.as_secs())), None),
+    };
+    CheckResult {
+        status: status.to_string(),
+        latency_ms,
+        error,
+        pending,
+    }
+}
+
+// Tulis lalu hapus file kecil; nama unik supaya probe paralel tidak bentrok
+async fn check_upload_dir(dir: &str) -> Result<Option<Vec<String>>, String> {
+    let path = format!("{}/.readyz-{}", dir, Uuid::new_v4());
+    tokio::fs::write(&path, b"ok")
+        .await
+        .map_err(|e| format!("{} is not writable: {}", dir, e))?;
+    tokio::fs::remove_file(&path)
+        .await
+        .map_err(|e| format!("Failed to clean up {}: {}", path, e))?;
+    Ok(None)
+}
+
+async fn check_migrations(db: &DatabaseConnection) -> Result<Option<Vec<String>>, String> {
+    let pending = Migrator::get_pending_migrations(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(Some(pending.iter().map(|m| m.name().to_string()).collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "health",
+    summary = "Liveness probe",
+    description = "Always 200 while the process can serve requests; no dependency checks.",
+    responses(
+        (status = 200, description = "Process is alive", body = MessageBody),
+    )
+)]
+pub async fn healthz() -> HttpResponse {
+    HttpResponse::Ok().json(ApiResponse::<()>::success("alive", None))
+}
+
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    summary = "Readiness probe",
+    description = "Checks the database connection, that the upload directory is writable \
+                   (local storage only) and that no migrations are pending. Returns 503 without \
+                   running the checks once the server has started shutting down.",
+    responses(
+        (status = 200, description = "All dependencies are up", body = ApiResponse<ReadinessReport>),
+        (status = 503, description = "At least one check failed, or the server is shutting down", body = ApiResponse<ReadinessReport>),
+    )
+)]
+pub async fn readyz(
+    db: web::Data<DatabaseConnection>,
+    settings: web::Data<Settings>,
+    shutdown: web::Data<Shutdown>,
+) -> HttpResponse {
+    // Load balancer berhenti mengirim trafik sebelum server berhenti menerima koneksi
+    if shutdown.is_draining() {
+        return HttpResponse::ServiceUnavailable().json(ApiResponse::<()>::error("Shutting down"));
+    }
+
+    let database = run_check(async { db.ping().await.map(|_| None).map_err(|e| e.to_string()) }).await;
+
+    let upload_dir = if settings.storage.backend == "local" {
+        run_check(check_upload_dir(&settings.storage.upload_dir)).await
+    } else {
+        CheckResult::skipped("storage backend is not local")
+    };
+
+    let migrations = if database.failed() {
+        CheckResult::skipped("database unavailable")
+    } else {
+        let mut result = run_check(check_migrations(db.get_ref())).await;
+        // Ada migrasi yang belum dijalankan: skema tidak cocok dengan kode
+        if let Some(pending) = result.pending.as_ref().filter(|p| !p.is_empty()) {
+            result.error = Some(format!("{} pending migration(s)", pending.len()));
+            result.status = FAIL.to_string();
+        }
+        result
+    };
+
+    let report = ReadinessReport {
+        database,
+        upload_dir,
+        migrations,
+    };
+    if report.database.failed() || report.upload_dir.failed() || report.migrations.failed() {
+        HttpResponse::ServiceUnavailable().json(ApiResponse::error_with("Not ready", report))
+    } else {
+        HttpResponse::Ok().json(ApiResponse::success("ready", Some(report)))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/version",
+    tag = "health",
+    summary = "Build information",
+    responses(
+        (status = 200, description = "Version and build metadata", body = ApiResponse<VersionInfo>),
+    )
+)]
+pub async fn version() -> HttpResponse {
+    let info = VersionInfo {
+        name: env!("CARGO_PKG_NAME").to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        // Build arg Docker yang tidak diisi menghasilkan string kosong
+        git_sha: option_env!("GIT_SHA").filter(|s| !s.is_empty()).map(str::to_string),
+        build_time: option_env!("BUILD_TIME").filter(|s| !s.is_empty()).map(str::to_string),
+        profile: if cfg!(debug_assertions) { "debug" } else { "release" }.to_string(),
+    };
+    HttpResponse::Ok().json(ApiResponse::success("Version info", Some(info)))
+}
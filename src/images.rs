@@ -0,0 +1,144 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ImageFormat, ImageReader};
+use std::fmt;
+use std::io::Cursor;
+
+const THUMBNAIL_SIZE: u32 = 320;
+const MEDIUM_SIZE: u32 = 1024;
+const JPEG_QUALITY: u8 = 85;
+
+#[derive(Debug)]
+pub enum ImageError {
+    UnsupportedFormat,
+    TooManyPixels { width: u32, height: u32, max: u32 },
+    Decode(String),
+    Encode(String),
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::UnsupportedFormat => {
+                write!(f, "Unsupported image format. Allowed: JPEG, PNG, WebP")
+            }
+            ImageError::TooManyPixels { width, height, max } => write!(
+                f,
+                "Image dimensions {}x{} exceed maximum of {}x{}",
+                width, height, max, max
+            ),
+            ImageError::Decode(e) => write!(f, "Failed to decode image: {}", e),
+            ImageError::Encode(e) => write!(f, "Failed to encode image: {}", e),
+        }
+    }
+}
+
+pub struct Variant {
+    pub name: &'static str,
+    pub extension: &'static str,
+    pub content_type: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+impl Variant {
+    pub fn filename(&self) -> String {
+        format!("{}.{}", self.name, self.extension)
+    }
+}
+
+pub struct ProcessedImage {
+    pub original: Variant,
+    pub thumbnail: Variant,
+    pub medium: Variant,
+    pub webp: Variant,
+}
+
+impl ProcessedImage {
+    pub fn variants(&self) -> [&Variant; 4] {
+        [&self.original, &self.thumbnail, &self.medium, &self.webp]
+    }
+}
+
+/// Validate uploaded bytes as a real image and produce the banner variants.
+///
+/// The format is sniffed from magic bytes (the client's filename is ignored) and
+/// every variant is re-encoded from decoded pixels, so EXIF and other metadata
+/// never reach disk. Byte limits are enforced earlier, while the upload streams in.
+pub fn process(bytes: &[u8], max_dimension: u32) -> Result<ProcessedImage, ImageError> {
+    let format = image::guess_format(bytes).map_err(|_| ImageError::UnsupportedFormat)?;
+    if !matches!(format, ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP) {
+        return Err(ImageError::UnsupportedFormat);
+    }
+
+    // Cek dimensi dari header sebelum decode penuh
+    let reader = ImageReader::with_format(Cursor::new(bytes), format);
+    let (width, height) = reader
+        .into_dimensions()
+        .map_err(|e| ImageError::Decode(e.to_string()))?;
+    if width > max_dimension || height > max_dimension {
+        return Err(ImageError::TooManyPixels {
+            width,
+            height,
+            max: max_dimension,
+        });
+    }
+
+    let img = image::load_from_memory_with_format(bytes, format)
+        .map_err(|e| ImageError::Decode(e.to_string()))?;
+
+    // PNG tetap PNG (transparansi), selain itu disimpan sebagai JPEG
+    let keep_png = format == ImageFormat::Png;
+
+    Ok(ProcessedImage {
+        original: encode("original", &img, keep_png)?,
+        thumbnail: encode("thumbnail", &shrink(&img, THUMBNAIL_SIZE), keep_png)?,
+        medium: encode("medium", &shrink(&img, MEDIUM_SIZE), keep_png)?,
+        webp: encode_webp("original", &img)?,
+    })
+}
+
+fn shrink(img: &DynamicImage, max: u32) -> DynamicImage {
+    if img.width() <= max && img.height() <= max {
+        img.clone()
+    } else {
+        img.thumbnail(max, max)
+    }
+}
+
+fn encode(name: &'static str, img: &DynamicImage, png: bool) -> Result<Variant, ImageError> {
+    let mut bytes = Vec::new();
+    if png {
+        img.write_with_encoder(PngEncoder::new(&mut bytes))
+            .map_err(|e| ImageError::Encode(e.to_string()))?;
+        Ok(Variant {
+            name,
+            extension: "png",
+            content_type: "image/png",
+            bytes,
+        })
+    } else {
+        DynamicImage::ImageRgb8(img.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY))
+            .map_err(|e| ImageError::Encode(e.to_string()))?;
+        Ok(Variant {
+            name,
+            extension: "jpg",
+            content_type: "image/jpeg",
+            bytes,
+        })
+    }
+}
+
+fn encode_webp(name: &'static str, img: &DynamicImage) -> Result<Variant, ImageError> {
+    let mut bytes = Vec::new();
+    DynamicImage::ImageRgba8(img.to_rgba8())
+        .write_with_encoder(WebPEncoder::new_lossless(&mut bytes))
+        .map_err(|e| ImageError::Encode(e.to_string()))?;
+    Ok(Variant {
+        name,
+        extension: "webp",
+        content_type: "image/webp",
+        bytes,
+    })
+}
@@ -0,0 +1,192 @@
+use chrono::{Duration, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, TransactionTrait};
+use std::sync::Arc;
+
+use crate::cache::ActiveBannerCache;
+use crate::config::{MediaGcConfig, TrashPurgeConfig, WebhookConfig};
+use crate::entity::{banner, banner_review, banner_revision, banner_translation};
+use crate::events::{self, ActiveState, BannerEvents};
+use crate::media;
+use crate::shutdown::Shutdown;
+use crate::storage::BlobStore;
+use crate::webhooks;
+
+// Watcher banner aktif: bangun sedikit setelah batas jadwal, paling lama tiap menit
+const WATCHER_BOUNDARY_SLACK: std::time::Duration = std::time::Duration::from_millis(50);
+const WATCHER_MAX_WAIT: std::time::Duration = std::time::Duration::from_secs(60);
+const WATCHER_RETRY: std::time::Duration = std::time::Duration::from_secs(5);
+
+// Saat shutdown job berhenti di antara dua putaran; putaran yang sedang jalan diselesaikan
+
+// Sweeper periodik untuk file upload yang tidak direferensikan banner mana pun
+pub fn spawn_media_gc(
+    shutdown: &Shutdown,
+    db: DatabaseConnection,
+    store: Arc<dyn BlobStore>,
+    cfg: MediaGcConfig,
+) {
+    let stop = shutdown.clone();
+    shutdown.spawn(async move {
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(cfg.interval_secs));
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = stop.stopping() => break,
+            }
+            match media::sweep(&db, store.as_ref(), Duration::seconds(cfg.grace_secs), false).await {
+                Ok(report) if report.deleted > 0 => tracing::info!(
+                    deleted = report.deleted,
+                    freed_bytes = report.freed_bytes,
+                    "Media GC: removed orphaned files"
+                ),
+                Ok(_) => {}
+                Err(e) => tracing::error!(error = %e, "Media GC failed"),
+            }
+        }
+    });
+}
+
+// Hapus permanen banner yang sudah melewati masa retensi di trash
+pub fn spawn_trash_purge(shutdown: &Shutdown, db: DatabaseConnection, cfg: TrashPurgeConfig) {
+    let stop = shutdown.clone();
+    shutdown.spawn(async move {
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(cfg.interval_secs));
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = stop.stopping() => break,
+            }
+            match purge_trash(&db, Duration::days(cfg.retention_days)).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!(deleted = n, "Trash purge: permanently deleted banners"),
+                Err(e) => tracing::error!(error = %e, "Trash purge failed"),
+            }
+        }
+    });
+}
+
+async fn purge_trash(db: &DatabaseConnection, retention: Duration) -> Result<usize, DbErr> {
+    let cutoff = Utc::now().naive_utc() - retention;
+    let expired = banner::Entity::find()
+        .filter(banner::Column::DeletedAt.lte(cutoff))
+        .all(db)
+        .await?;
+
+    let mut purged = 0;
+    for b in expired {
+        // Syarat trash diulang saat delete: banner yang baru di-restore tidak ikut terhapus.
+        // Referensi gambar dilepas bersamaan, file-nya nanti dibersihkan media GC
+        let txn = db.begin().await?;
+        let res = banner::Entity::delete_many()
+            .filter(banner::Column::Id.eq(b.id))
+            .filter(banner::Column::DeletedAt.lte(cutoff))
+            .exec(&txn)
+            .await?;
+        if res.rows_affected == 1 {
+            // Tidak ada foreign key di skema: terjemahan, review dan revisi dihapus manual
+            banner_translation::Entity::delete_many()
+                .filter(banner_translation::Column::BannerId.eq(b.id))
+                .exec(&txn)
+                .await?;
+            banner_review::Entity::delete_many()
+                .filter(banner_review::Column::BannerId.eq(b.id))
+                .exec(&txn)
+                .await?;
+            banner_revision::Entity::delete_many()
+                .filter(banner_revision::Column::BannerId.eq(b.id))
+                .exec(&txn)
+                .await?;
+            media::release(&txn, &media::banner_keys(&b)).await?;
+            purged += 1;
+        }
+        txn.commit().await?;
+    }
+    Ok(purged)
+}
+
+// Hitung ulang banner aktif setiap ada write atau saat batas jadwal terlewati,
+// lalu kirim event ke stream kalau hasilnya berubah
+pub fn spawn_banner_watcher(
+    shutdown: &Shutdown,
+    db: DatabaseConnection,
+    cache: Arc<ActiveBannerCache>,
+    events: Arc<BannerEvents>,
+) {
+    let stop = shutdown.clone();
+    shutdown.spawn(async move {
+        let mut last: Option<ActiveState> = None;
+        loop {
+            let now = Utc::now().naive_utc();
+            let wait = match cache.get_or_load(&db).await {
+                Ok(active) => {
+                    let state = ActiveState::at(&active, now);
+                    if last.as_ref() != Some(&state) {
+                        events.publish(&state);
+                        last = Some(state);
+                    }
+                    events::next_boundary(&active, now)
+                        .and_then(|next| (next - now).to_std().ok())
+                        .map(|d| d + WATCHER_BOUNDARY_SLACK)
+                        .unwrap_or(WATCHER_MAX_WAIT)
+                        .min(WATCHER_MAX_WAIT)
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "Banner watcher failed");
+                    WATCHER_RETRY
+                }
+            };
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = cache.invalidated() => {}
+                _ = stop.stopping() => break,
+            }
+        }
+    });
+}
+
+// Kirim antrean webhook yang sudah jatuh tempo
+pub fn spawn_webhook_dispatcher(shutdown: &Shutdown, db: DatabaseConnection, cfg: WebhookConfig) {
+    let stop = shutdown.clone();
+    shutdown.spawn(async move {
+        let client = match reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(cfg.timeout_secs))
+            .user_agent(concat!("rest_api-webhooks/", env!("CARGO_PKG_VERSION")))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!(error = %e, "Webhook dispatcher disabled");
+                return;
+            }
+        };
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(cfg.poll_interval_secs));
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = stop.stopping() => break,
+            }
+            if let Err(e) = webhooks::dispatch_due(&db, &client, &cfg).await {
+                tracing::error!(error = %e, "Webhook dispatch failed");
+            }
+        }
+    });
+}
+
+// Banner published yang lewat end_date tidak menulis apa pun, jadi dicek berkala
+pub fn spawn_banner_expiry(shutdown: &Shutdown, db: DatabaseConnection, cfg: WebhookConfig) {
+    let stop = shutdown.clone();
+    shutdown.spawn(async move {
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(cfg.expiry_check_secs));
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = stop.stopping() => break,
+            }
+            match webhooks::expire_banners(&db).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!(queued = n, "Banner expiry: queued banner.expired events"),
+                Err(e) => tracing::error!(error = %e, "Banner expiry check failed"),
+            }
+        }
+    });
+}
@@ -0,0 +1,77 @@
+use actix_web::HttpRequest;
+use actix_web::http::header;
+use std::collections::HashMap;
+
+/// Locale default saat tidak ada terjemahan yang cocok dengan permintaan client.
+#[derive(Clone)]
+pub struct DefaultLocale(pub String);
+
+// Tag bahasa sederhana: "id", "en", "en-us", "zh-hant-tw"
+pub fn is_valid(tag: &str) -> bool {
+    let mut parts = tag.split('-');
+    let primary = parts.next().unwrap_or("");
+    (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|p| (1..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+pub fn normalize(tag: &str) -> String {
+    tag.trim().replace('_', "-").to_ascii_lowercase()
+}
+
+/// Parse `Accept-Language` into tags ordered by q-value (highest first).
+fn accept_language(value: &str) -> Vec<String> {
+    let mut tags: Vec<(String, f32)> = value
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let tag = normalize(parts.next()?);
+            let q = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (q > 0.0 && (tag == "*" || is_valid(&tag))).then_some((tag, q))
+        })
+        .collect();
+    // sort stabil: urutan asli dipertahankan untuk q yang sama
+    tags.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    tags.into_iter()
+        .map(|(tag, _)| tag)
+        .filter(|tag| tag != "*")
+        .collect()
+}
+
+/// Urutan locale yang dicoba untuk request ini.
+///
+/// `?locale=` override menang, lalu `Accept-Language` sesuai q-value. Setiap tag
+/// regional diikuti bahasa induknya (`en-us` -> `en`), dan locale default ada
+/// di paling akhir. Kalau semuanya tidak ada, konten asli banner yang dipakai.
+pub fn fallback_chain(
+    req: &HttpRequest,
+    query: &HashMap<String, String>,
+    default: &DefaultLocale,
+) -> Vec<String> {
+    let mut requested = Vec::new();
+    if let Some(tag) = query.get("locale").map(|l| normalize(l)).filter(|l| is_valid(l)) {
+        requested.push(tag);
+    }
+    if let Some(value) = req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+    {
+        requested.extend(accept_language(value));
+    }
+    requested.push(normalize(&default.0));
+
+    let mut chain: Vec<String> = Vec::new();
+    for tag in requested {
+        let primary = tag.split('-').next().unwrap_or(&tag).to_string();
+        for candidate in [tag, primary] {
+            if !chain.contains(&candidate) {
+                chain.push(candidate);
+            }
+        }
+    }
+    chain
+}
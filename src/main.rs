@@ -1,43 +1,211 @@
+mod auth;
+mod cache;
 mod config;
 mod handlers;
+mod health;
+mod images;
+mod jobs;
+mod locale;
+mod markdown;
+mod media;
+mod metrics;
 mod models;
+mod openapi;
+mod presence;
+mod ratelimit;
 mod responses;
+mod revisions;
 mod routes;
+mod schedule;
+mod shutdown;
 mod entity;
+mod events;
+mod storage;
+mod telemetry;
+mod uploads;
+mod webhooks;
+mod validation;
+mod workflow;
 
-use actix_web::{App, HttpServer, middleware::DefaultHeaders};
-use actix_web::http::header;
+use actix_web::dev::Service as _;
+use actix_web::{App, HttpServer, middleware, web};
+use actix_web::http::header::{self, HeaderValue};
 use dotenvy::dotenv;
-use std::env;
-use env_logger;
 use std::fs;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::Instrument as _;
+
+use crate::auth::ApiTokens;
+use crate::locale::DefaultLocale;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
-    env_logger::init();
+
+    let settings = match config::Settings::load() {
+        Ok(settings) => settings,
+        Err(e) => {
+            // Logger belum terpasang karena formatnya sendiri bagian dari konfigurasi
+            eprintln!("Invalid configuration:\n{}", e);
+            std::process::exit(1);
+        }
+    };
+    telemetry::init(&settings.log);
 
     // Create uploads directory if it doesn't exist
-    fs::create_dir_all("./uploads/banners").unwrap_or_else(|e| {
-        eprintln!("Warning: Failed to create uploads directory: {}", e);
+    let upload_dir = settings.storage.upload_dir.clone();
+    fs::create_dir_all(format!("{}/banners", upload_dir)).unwrap_or_else(|e| {
+        tracing::warn!(dir = %upload_dir, error = %e, "Failed to create uploads directory");
     });
 
-    let db = config::init_db().await;
+    let db = config::init_db(&settings.database).await;
+    let store = web::Data::from(config::init_storage(&settings.storage));
+    let media_access = web::Data::new(config::media_access(&settings.storage));
+    let api_tokens = web::Data::new(ApiTokens::parse(&settings.auth.api_tokens));
+    let default_locale = web::Data::new(DefaultLocale(settings.i18n.default_locale.clone()));
+    let active_cache = web::Data::new(cache::ActiveBannerCache::new(&settings.http_cache));
+    let banner_events = web::Data::new(events::BannerEvents::default());
+    let presence = web::Data::new(presence::PresenceHub::default());
+    let metrics = web::Data::new(metrics::Metrics::default());
+    let rate_limiter = web::Data::new(ratelimit::RateLimiter::new(
+        settings.rate_limit.clone(),
+        Arc::new(ratelimit::MemoryStore::default()),
+    ));
+
+    let shutdown = shutdown::Shutdown::default();
 
-    let host = env::var("APP_HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
-    let port = env::var("APP_PORT").unwrap_or_else(|_| "8080".to_string());
-    let bind = format!("{}:{}", host, port);
+    jobs::spawn_media_gc(&shutdown, db.clone(), store.clone().into_inner(), settings.media_gc.clone());
+    jobs::spawn_trash_purge(&shutdown, db.clone(), settings.trash_purge.clone());
+    jobs::spawn_webhook_dispatcher(&shutdown, db.clone(), settings.webhooks.clone());
+    jobs::spawn_banner_expiry(&shutdown, db.clone(), settings.webhooks.clone());
+    jobs::spawn_banner_watcher(
+        &shutdown,
+        db.clone(),
+        active_cache.clone().into_inner(),
+        banner_events.clone().into_inner(),
+    );
 
-    println!("Server running at http://{}", bind);
+    let bind = settings.bind_address();
+    let cors = settings.cors.clone();
+    let shutdown_cfg = settings.shutdown.clone();
+    let settings = web::Data::new(settings);
+    let pool = db.clone();
+    let shutdown_data = web::Data::new(shutdown.clone());
 
-    HttpServer::new(move || {
+    tracing::info!(address = %bind, "Server running");
+
+    // Sinyal ditangani sendiri (watch_signals) supaya readiness sempat 503 sebelum berhenti
+    let server = HttpServer::new(move || {
         App::new()
-            .app_data(actix_web::web::Data::new(db.clone()))
-            .wrap(DefaultHeaders::new().add((header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")))
-            .service(actix_files::Files::new("/uploads", "./uploads").show_files_listing())
+            .app_data(web::Data::new(db.clone()))
+            .app_data(store.clone())
+            .app_data(media_access.clone())
+            .app_data(settings.clone())
+            .app_data(api_tokens.clone())
+            .app_data(default_locale.clone())
+            .app_data(active_cache.clone())
+            .app_data(banner_events.clone())
+            .app_data(presence.clone())
+            .app_data(metrics.clone())
+            .app_data(rate_limiter.clone())
+            .app_data(shutdown_data.clone())
+            .app_data(validation::json_config())
+            .app_data(validation::query_config())
+            .app_data(validation::path_config())
+            // 429 dibuat di dalam CORS supaya browser tetap bisa membaca header Retry-After
+            .wrap(middleware::from_fn(ratelimit::middleware))
+            .wrap(config::cors(&cors))
+            // Jumlah & durasi request per pola route (bukan path asli, mis. /api/banners/{id})
+            .wrap_fn(|req, srv| {
+                let metrics = req.app_data::<web::Data<metrics::Metrics>>().cloned();
+                let method = req.method().to_string();
+                let route = req.match_pattern().unwrap_or_else(|| "unmatched".to_string());
+                let started = Instant::now();
+                let fut = srv.call(req);
+                async move {
+                    let res = fut.await?;
+                    if let Some(metrics) = metrics {
+                        let elapsed = started.elapsed().as_secs_f64();
+                        metrics.observe_request(&method, &route, res.status().as_u16(), elapsed);
+                    }
+                    Ok(res)
+                }
+            })
+            // Paling luar: X-Request-Id dan span per request, dipakai juga oleh ApiResponse::error
+            .wrap_fn(|req, srv| {
+                let request_id = telemetry::request_id(&req);
+                let span = tracing::info_span!(
+                    "http_request",
+                    request_id = %request_id,
+                    method = %req.method(),
+                    path = %req.path(),
+                    route = req.match_pattern().as_deref().unwrap_or("unmatched"),
+                );
+                let started = Instant::now();
+                let fut = span.in_scope(|| srv.call(req));
+                let header_value = HeaderValue::from_str(&request_id).ok();
+                telemetry::scope(
+                    request_id,
+                    async move {
+                        let mut res = fut.await?;
+                        if let Some(value) = header_value {
+                            res.headers_mut().insert(telemetry::REQUEST_ID_HEADER, value);
+                        }
+                        tracing::info!(
+                            status = res.status().as_u16(),
+                            latency_ms = started.elapsed().as_secs_f64() * 1000.0,
+                            "request completed"
+                        );
+                        Ok(res)
+                    }
+                    .instrument(span),
+                )
+            })
+            .service(
+                web::scope("/uploads")
+                    // nosniff di semua response; cache lama untuk file publik (key unik per upload)
+                    .wrap_fn(|req, srv| {
+                        let fut = srv.call(req);
+                        async move {
+                            let mut res = fut.await?;
+                            res.headers_mut().insert(
+                                header::X_CONTENT_TYPE_OPTIONS,
+                                HeaderValue::from_static("nosniff"),
+                            );
+                            if res.status().is_success() && !res.headers().contains_key(header::CACHE_CONTROL) {
+                                res.headers_mut().insert(
+                                    header::CACHE_CONTROL,
+                                    HeaderValue::from_static(cache::UPLOADS_CACHE_CONTROL),
+                                );
+                            }
+                            Ok(res)
+                        }
+                    })
+                    .route("/{key:.*}", web::get().to(uploads::serve))
+                    .route("/{key:.*}", web::head().to(uploads::serve)),
+            )
             .configure(routes::configure)
     })
+        .shutdown_timeout(shutdown_cfg.timeout_secs)
+        .disable_signals()
         .bind(bind)?
-        .run()
-        .await
+        .run();
+
+    actix_web::rt::spawn(shutdown::watch_signals(server.handle(), shutdown.clone(), shutdown_cfg.clone()));
+    let result = server.await;
+
+    // Server sudah berhenti: hentikan job, baru tutup pool DB
+    let timeout = Duration::from_secs(shutdown_cfg.timeout_secs);
+    if !shutdown.stop_jobs(timeout).await {
+        tracing::warn!(timeout_secs = shutdown_cfg.timeout_secs, "Background jobs did not stop in time");
+    }
+    // close() menunggu koneksi yang masih dipinjam, jadi ikut dibatasi timeout
+    match tokio::time::timeout(timeout, pool.close()).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => tracing::warn!(error = %e, "Failed to close database pool"),
+        Err(_) => tracing::warn!("Timed out closing database pool"),
+    }
+    tracing::info!("Shutdown complete");
+    result
 }
@@ -0,0 +1,112 @@
+use ammonia::Builder;
+use pulldown_cmark::{Options, Parser, html};
+use std::collections::HashSet;
+use url::Url;
+
+use crate::validation::FieldError;
+
+pub const MAX_CTA_LABEL_LEN: usize = 100;
+
+// Tag HTML yang boleh muncul di konten banner
+const ALLOWED_TAGS: &[&str] = &[
+    "p", "br", "strong", "em", "del", "a", "ul", "ol", "li", "code", "pre", "blockquote", "h3",
+    "h4", "hr",
+];
+const ALLOWED_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Render banner Markdown into HTML safe to inject into a client page.
+///
+/// Raw HTML in the source is sanitized along with the rendered output, so
+/// `<script>`, `<style>`, event handlers and `javascript:` links never survive.
+pub fn render(source: &str) -> String {
+    let parser = Parser::new_ext(source, Options::ENABLE_STRIKETHROUGH);
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, parser);
+
+    Builder::default()
+        .tags(ALLOWED_TAGS.iter().copied().collect::<HashSet<_>>())
+        .url_schemes(ALLOWED_SCHEMES.iter().copied().collect::<HashSet<_>>())
+        .link_rel(Some("noopener noreferrer"))
+        .clean(&unsafe_html)
+        .to_string()
+}
+
+/// Validasi tombol CTA: label dan URL harus diisi berpasangan, URL http(s) absolut.
+pub fn validate_cta(label: Option<&str>, url: Option<&str>) -> Result<(), FieldError> {
+    match (label, url) {
+        (None, None) => Ok(()),
+        (Some(_), None) => Err(FieldError::new("cta_url", "is required when cta_label is set")),
+        (None, Some(_)) => Err(FieldError::new("cta_label", "is required when cta_url is set")),
+        (Some(label), Some(url)) => {
+            let label = label.trim();
+            if label.is_empty() || label.chars().count() > MAX_CTA_LABEL_LEN {
+                return Err(FieldError::new(
+                    "cta_label",
+                    format!("must be between 1 and {} characters", MAX_CTA_LABEL_LEN),
+                ));
+            }
+            validate_cta_url(url)
+        }
+    }
+}
+
+pub fn validate_cta_url(url: &str) -> Result<(), FieldError> {
+    match Url::parse(url) {
+        Ok(u) if u.scheme() == "http" || u.scheme() == "https" => Ok(()),
+        _ => Err(FieldError::new("cta_url", "must be an absolute http or https URL")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_script_tags() {
+        let html = render("Halo <script>alert(1)</script> dunia");
+        assert!(!html.contains("<script"), "{}", html);
+        assert!(!html.contains("alert(1)"), "{}", html);
+        assert!(html.contains("Halo"));
+    }
+
+    #[test]
+    fn strips_event_handlers() {
+        let html = render(r#"<img src="x" onerror="alert(1)"> <a href="https://a.com" onclick="x()">a</a>"#);
+        assert!(!html.contains("onerror"), "{}", html);
+        assert!(!html.contains("onclick"), "{}", html);
+    }
+
+    #[test]
+    fn neutralizes_javascript_links() {
+        let html = render("[x](javascript:alert(1))");
+        assert!(!html.contains("javascript:"), "{}", html);
+        assert!(html.contains(">x</a>"), "{}", html); // teks link tetap ada, hanya href dibuang
+    }
+
+    #[test]
+    fn adds_noopener_to_links() {
+        let html = render("[situs](https://example.com)");
+        assert!(html.contains(r#"href="https://example.com""#), "{}", html);
+        assert!(html.contains(r#"rel="noopener noreferrer""#), "{}", html);
+    }
+
+    #[test]
+    fn cta_label_and_url_must_come_together() {
+        assert_eq!(validate_cta(None, None), Ok(()));
+        assert_eq!(validate_cta(Some("Daftar"), Some("https://example.com/daftar")), Ok(()));
+        assert_eq!(validate_cta(Some("Daftar"), None).unwrap_err().field, "cta_url");
+        assert_eq!(validate_cta(None, Some("https://example.com")).unwrap_err().field, "cta_label");
+        assert_eq!(validate_cta(Some("  "), Some("https://example.com")).unwrap_err().field, "cta_label");
+        let long = "a".repeat(MAX_CTA_LABEL_LEN + 1);
+        assert_eq!(validate_cta(Some(&long), Some("https://example.com")).unwrap_err().field, "cta_label");
+    }
+
+    #[test]
+    fn cta_url_must_be_absolute_http() {
+        for url in ["ftp://example.com/file", "javascript:alert(1)", "/relative/path", "example.com"] {
+            let err = validate_cta(Some("Buka"), Some(url)).unwrap_err();
+            assert_eq!(err.field, "cta_url", "{}", url);
+        }
+        assert_eq!(validate_cta(Some("Buka"), Some("http://example.com")), Ok(()));
+    }
+}
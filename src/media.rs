@@ -0,0 +1,173 @@
+use chrono::{Duration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QuerySelect,
+    Set,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::entity::{banner, media};
+use crate::storage::BlobStore;
+
+// Semua key gambar yang dipakai sebuah banner
+pub fn banner_keys(b: &banner::Model) -> Vec<String> {
+    [&b.image_url, &b.image_thumbnail_url, &b.image_medium_url, &b.image_webp_url]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect()
+}
+
+// Setiap upload punya prefix sendiri: banners/<upload_id>/<varian>.<ext>
+pub fn upload_prefix(upload_id: Uuid) -> String {
+    format!("banners/{}", upload_id)
+}
+
+// Key yang masih dilacak untuk satu upload; kosong kalau tidak dikenal atau sudah dibersihkan GC
+pub async fn upload_keys<C: ConnectionTrait>(db: &C, upload_id: Uuid) -> Result<Vec<String>, DbErr> {
+    let keys = media::Entity::find()
+        .filter(media::Column::StorageKey.starts_with(format!("{}/", upload_prefix(upload_id))))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|m| m.storage_key)
+        .collect();
+    Ok(keys)
+}
+
+/// Record freshly uploaded objects. They start unreferenced, so the sweeper
+/// removes them after the grace period unless a banner picks them up.
+pub async fn register<C: ConnectionTrait>(
+    db: &C,
+    objects: &[(String, usize)],
+) -> Result<(), DbErr> {
+    let now = Utc::now().naive_utc();
+    for (key, size) in objects {
+        media::ActiveModel {
+            storage_key: Set(key.clone()),
+            size_bytes: Set(*size as i64),
+            ref_count: Set(0),
+            unreferenced_since: Set(Some(now)),
+            created_at: Set(Some(now)),
+            updated_at: Set(Some(now)),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Tambah referensi dan kembalikan jumlah row yang kena.
+///
+/// Key yang tidak dilacak (data lama) diabaikan. Hasil di bawah `keys.len()` untuk key
+/// yang seharusnya dilacak berarti sweeper sudah menghapusnya: caller harus rollback.
+pub async fn acquire<C: ConnectionTrait>(db: &C, keys: &[String]) -> Result<u64, DbErr> {
+    if keys.is_empty() {
+        return Ok(0);
+    }
+    let res = media::Entity::update_many()
+        .col_expr(media::Column::RefCount, Expr::col(media::Column::RefCount).add(1))
+        .col_expr(media::Column::UnreferencedSince, Expr::value(Option::<chrono::NaiveDateTime>::None))
+        .col_expr(media::Column::UpdatedAt, Expr::value(Utc::now().naive_utc()))
+        .filter(media::Column::StorageKey.is_in(keys.iter().cloned()))
+        .exec(db)
+        .await?;
+    Ok(res.rows_affected)
+}
+
+// True kalau semua key masih ada di blob store (URL lama di luar tabel media dianggap ada).
+// Row-nya dikunci sampai transaksi selesai, jadi sweeper tidak bisa menghapusnya sebelum acquire()
+pub async fn all_tracked<C: ConnectionTrait>(db: &C, keys: &[String]) -> Result<bool, DbErr> {
+    let tracked: Vec<&String> = keys.iter().filter(|k| !crate::storage::is_url(k)).collect();
+    if tracked.is_empty() {
+        return Ok(true);
+    }
+    let found = media::Entity::find()
+        .filter(media::Column::StorageKey.is_in(tracked.iter().map(|k| k.to_string())))
+        .lock_exclusive()
+        .all(db)
+        .await?
+        .len();
+    Ok(found == tracked.len())
+}
+
+// Kurangi referensi; yang sampai nol mulai dihitung masa tenggangnya
+pub async fn release<C: ConnectionTrait>(db: &C, keys: &[String]) -> Result<(), DbErr> {
+    if keys.is_empty() {
+        return Ok(());
+    }
+    let now = Utc::now().naive_utc();
+    media::Entity::update_many()
+        .col_expr(media::Column::RefCount, Expr::col(media::Column::RefCount).sub(1))
+        .col_expr(media::Column::UpdatedAt, Expr::value(now))
+        .filter(media::Column::StorageKey.is_in(keys.iter().cloned()))
+        .filter(media::Column::RefCount.gt(0))
+        .exec(db)
+        .await?;
+    media::Entity::update_many()
+        .col_expr(media::Column::UnreferencedSince, Expr::value(now))
+        .filter(media::Column::StorageKey.is_in(keys.iter().cloned()))
+        .filter(media::Column::RefCount.eq(0))
+        .filter(media::Column::UnreferencedSince.is_null())
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SweepReport {
+    pub dry_run: bool,
+    pub deleted: usize,
+    pub freed_bytes: i64,
+    pub keys: Vec<String>,
+}
+
+/// Delete objects that have had no references for longer than `grace`.
+///
+/// With `dry_run` nothing is touched; the report lists what would be removed.
+pub async fn sweep<C: ConnectionTrait>(
+    db: &C,
+    store: &dyn BlobStore,
+    grace: Duration,
+    dry_run: bool,
+) -> Result<SweepReport, DbErr> {
+    let cutoff = Utc::now().naive_utc() - grace;
+    let orphans = media::Entity::find()
+        .filter(media::Column::RefCount.eq(0))
+        .filter(media::Column::UnreferencedSince.lte(cutoff))
+        .all(db)
+        .await?;
+
+    let mut report = SweepReport {
+        dry_run,
+        deleted: 0,
+        freed_bytes: 0,
+        keys: Vec::with_capacity(orphans.len()),
+    };
+
+    for row in orphans {
+        if !dry_run {
+            // Hapus row dulu dengan syarat masih tanpa referensi. Kalau row sudah terhapus,
+            // acquire() yang datang belakangan mengembalikan jumlah kurang dan caller-nya rollback
+            let res = media::Entity::delete_many()
+                .filter(media::Column::Id.eq(row.id))
+                .filter(media::Column::RefCount.eq(0))
+                .exec(db)
+                .await?;
+            if res.rows_affected == 0 {
+                continue;
+            }
+            if let Err(e) = store.delete(&row.storage_key).await {
+                tracing::warn!(key = %row.storage_key, error = %e, "Media GC: failed to delete");
+            }
+        }
+        report.deleted += 1;
+        report.freed_bytes += row.size_bytes;
+        report.keys.push(row.storage_key);
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,183 @@
+use actix_web::{HttpResponse, web};
+use chrono::Utc;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry, TextEncoder,
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter};
+
+use crate::auth::{self, Actor};
+use crate::cache::ActiveBannerCache;
+use crate::entity::attendance;
+use crate::events::ActiveState;
+use crate::openapi::MessageBody;
+
+/// Registry Prometheus untuk `/metrics`.
+///
+/// Counter HTTP dan upload diisi saat request berjalan; gauge pool DB, sesi
+/// attendance terbuka dan banner aktif dihitung ulang setiap kali di-scrape.
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_duration: HistogramVec,
+    db_pool: IntGaugeVec,
+    upload_bytes: IntCounterVec,
+    uploads: IntCounter,
+    rate_limited: IntCounterVec,
+    open_sessions: IntGauge,
+    active_banners: IntGauge,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by route pattern and status"),
+            &["method", "route", "status"],
+        )
+        .unwrap();
+        let http_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency"),
+            &["method", "route"],
+        )
+        .unwrap();
+        let db_pool = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database pool connections by state"),
+            &["state"],
+        )
+        .unwrap();
+        let upload_bytes = IntCounterVec::new(
+            Opts::new(
+                "banner_upload_bytes_total",
+                "Banner image bytes received from clients and written to storage",
+            ),
+            &["kind"],
+        )
+        .unwrap();
+        let uploads = IntCounter::new("banner_uploads_total", "Banner images stored").unwrap();
+        let rate_limited = IntCounterVec::new(
+            Opts::new("http_rate_limited_total", "Requests rejected with 429 by rate limit policy"),
+            &["policy"],
+        )
+        .unwrap();
+        let open_sessions = IntGauge::new(
+            "attendance_open_sessions",
+            "Clock-ins without a clock-out",
+        )
+        .unwrap();
+        let active_banners = IntGauge::new(
+            "banners_active",
+            "Banners visible on /api/banners/active right now",
+        )
+        .unwrap();
+
+        let registry = Registry::new();
+        registry.register(Box::new(http_requests.clone())).unwrap();
+        registry.register(Box::new(http_duration.clone())).unwrap();
+        registry.register(Box::new(db_pool.clone())).unwrap();
+        registry.register(Box::new(upload_bytes.clone())).unwrap();
+        registry.register(Box::new(uploads.clone())).unwrap();
+        registry.register(Box::new(rate_limited.clone())).unwrap();
+        registry.register(Box::new(open_sessions.clone())).unwrap();
+        registry.register(Box::new(active_banners.clone())).unwrap();
+
+        Self {
+            registry,
+            http_requests,
+            http_duration,
+            db_pool,
+            upload_bytes,
+            uploads,
+            rate_limited,
+            open_sessions,
+            active_banners,
+        }
+    }
+}
+
+impl Metrics {
+    // `route` adalah pola resource (mis. /api/banners/{id}), bukan path asli, supaya label tidak meledak
+    pub fn observe_request(&self, method: &str, route: &str, status: u16, seconds: f64) {
+        self.http_requests
+            .with_label_values(&[method, route, &status.to_string()])
+            .inc();
+        self.http_duration
+            .with_label_values(&[method, route])
+            .observe(seconds);
+    }
+
+    pub fn record_upload(&self, received: usize, stored: usize) {
+        self.upload_bytes.with_label_values(&["received"]).inc_by(received as u64);
+        self.upload_bytes.with_label_values(&["stored"]).inc_by(stored as u64);
+        self.uploads.inc();
+    }
+
+    pub fn record_rate_limited(&self, policy: &str) {
+        self.rate_limited.with_label_values(&[policy]).inc();
+    }
+
+    // Gauge yang diambil dari DB; kalau query gagal nilai lama dipertahankan
+    async fn refresh(&self, db: &DatabaseConnection, cache: &ActiveBannerCache) {
+        if let DatabaseConnection::SqlxMySqlPoolConnection(_) = db {
+            let pool = db.get_mysql_connection_pool();
+            let size = pool.size() as i64;
+            let idle = pool.num_idle() as i64;
+            self.db_pool.with_label_values(&["idle"]).set(idle);
+            self.db_pool.with_label_values(&["active"]).set(size - idle);
+            self.db_pool
+                .with_label_values(&["max"])
+                .set(pool.options().get_max_connections() as i64);
+        }
+
+        match attendance::Entity::find()
+            .filter(attendance::Column::ClockOutTime.is_null())
+            .count(db)
+            .await
+        {
+            Ok(count) => self.open_sessions.set(count as i64),
+            Err(e) => tracing::warn!(error = %e, "metrics: failed to count open sessions"),
+        }
+
+        match cache.get_or_load(db).await {
+            Ok(active) => {
+                let state = ActiveState::at(&active, Utc::now().naive_utc());
+                self.active_banners.set(state.banners.len() as i64);
+            }
+            Err(e) => tracing::warn!(error = %e, "metrics: failed to load active banners"),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "health",
+    summary = "Prometheus metrics",
+    description = "Requires an admin token; configure the scraper with `authorization: { credentials: <token> }`.",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Prometheus text exposition format", content_type = "text/plain", body = String),
+        (status = 401, description = "Missing or invalid API token", body = MessageBody),
+        (status = 403, description = "Admin role required", body = MessageBody),
+    )
+)]
+pub async fn metrics(
+    db: web::Data<DatabaseConnection>,
+    cache: web::Data<ActiveBannerCache>,
+    metrics: web::Data<Metrics>,
+    actor: Actor,
+) -> HttpResponse {
+    // Isi metrics (route, volume request, sesi terbuka) tidak untuk publik
+    if !actor.is_admin() {
+        return auth::forbidden("Admin role required");
+    }
+    metrics.refresh(db.get_ref(), cache.get_ref()).await;
+
+    let encoder = TextEncoder::new();
+    let mut body = Vec::new();
+    if let Err(e) = encoder.encode(&metrics.registry.gather(), &mut body) {
+        return HttpResponse::InternalServerError().body(e.to_string());
+    }
+    HttpResponse::Ok()
+        .content_type(encoder.format_type())
+        .body(body)
+}
@@ -1,47 +1,379 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use chrono::NaiveDateTime;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+use crate::entity::{banner, banner_review, banner_revision, banner_translation, webhook_delivery, webhook_subscription};
+use crate::markdown;
+use crate::schedule::Recurrence;
+use crate::validation::{self, cross_field, not_blank};
 
-#[derive(Deserialize)]
+// Batas panjang mengikuti kolom database; content (TEXT) dibatasi supaya render Markdown tetap murah
+pub const MAX_TITLE_LEN: u64 = 255;
+pub const MAX_CONTENT_LEN: u64 = 10_000;
+
+#[derive(Deserialize, ToSchema, Validate)]
+#[serde(deny_unknown_fields)]
 pub struct ClockRequest {
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = 100, message = "must be at most 100 characters")
+    )]
     pub user_id: String,
+    #[validate(length(max = 100, message = "must be at most 100 characters"))]
+    pub department: Option<String>, // hanya dipakai saat clock-in
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct AttendanceDto {
     pub id: i32,
     pub user_id: String,
+    pub department: Option<String>,
     pub clock_in_time: NaiveDateTime,
     pub clock_out_time: Option<NaiveDateTime>,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema, Validate)]
+#[serde(deny_unknown_fields)]
+#[validate(schema(function = "validate_create_banner", skip_on_field_errors = false))]
 pub struct CreateBannerRequest {
+    #[validate(length(max = MAX_TITLE_LEN, message = "must be at most 255 characters"))]
     pub title: Option<String>,
-    pub content: String,
-    pub start_date: String, // Format: "2024-01-01 10:00:00"
-    pub end_date: String,   // Format: "2024-12-31 23:59:59"
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = MAX_CONTENT_LEN, message = "must be at most 10000 characters")
+    )]
+    pub content: String, // Markdown
+    #[validate(length(max = 100, message = "must be at most 100 characters"))]
+    pub cta_label: Option<String>,
+    #[validate(length(max = 500, message = "must be at most 500 characters"))]
+    pub cta_url: Option<String>,
+    #[serde(deserialize_with = "validation::datetime")]
+    #[schema(value_type = String, example = "2024-01-01 10:00:00")]
+    pub start_date: NaiveDateTime,
+    #[serde(deserialize_with = "validation::datetime")]
+    #[schema(value_type = String, example = "2024-12-31 23:59:59")]
+    pub end_date: NaiveDateTime,
+    #[validate(length(max = 500, message = "must be at most 500 characters"))]
+    pub recurrence_rule: Option<String>, // RRULE, mis. "FREQ=WEEKLY;BYDAY=MO;BYHOUR=8;BYMINUTE=0;BYSECOND=0"
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub recurrence_duration_minutes: Option<i32>,
+    #[validate(length(max = 64, message = "must be at most 64 characters"))]
+    pub recurrence_timezone: Option<String>, // IANA, mis. "Asia/Jakarta" (default UTC)
+    /// `upload_id` dari `POST /api/banners/upload`, dipasang sebagai gambar banner
+    #[schema(value_type = Option<String>, format = "uuid")]
+    pub image_upload_id: Option<Uuid>,
+}
+
+fn validate_create_banner(req: &CreateBannerRequest) -> Result<(), ValidationError> {
+    if req.start_date >= req.end_date {
+        return Err(cross_field("end_date", "must be after start_date"));
+    }
+    markdown::validate_cta(non_blank(&req.cta_label), non_blank(&req.cta_url))?;
+    if let Some(rule) = non_blank(&req.recurrence_rule) {
+        Recurrence::parse(
+            rule,
+            req.recurrence_duration_minutes,
+            req.recurrence_timezone.as_deref(),
+            req.start_date,
+        )?;
+    }
+    Ok(())
+}
+
+// "" berarti tidak diisi (atau dihapus, untuk update)
+fn non_blank(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema, Validate)]
+#[serde(deny_unknown_fields)]
+#[validate(schema(function = "validate_update_banner", skip_on_field_errors = false))]
 pub struct UpdateBannerRequest {
+    #[validate(length(max = MAX_TITLE_LEN, message = "must be at most 255 characters"))]
     pub title: Option<String>,
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = MAX_CONTENT_LEN, message = "must be at most 10000 characters")
+    )]
     pub content: Option<String>,
-    pub start_date: Option<String>,
-    pub end_date: Option<String>,
+    #[validate(length(max = 100, message = "must be at most 100 characters"))]
+    pub cta_label: Option<String>, // "" untuk menghapus CTA (bersama cta_url)
+    #[validate(length(max = 500, message = "must be at most 500 characters"))]
+    pub cta_url: Option<String>,
+    #[serde(default, deserialize_with = "validation::optional_datetime")]
+    #[schema(value_type = Option<String>, example = "2024-01-01 10:00:00")]
+    pub start_date: Option<NaiveDateTime>,
+    #[serde(default, deserialize_with = "validation::optional_datetime")]
+    #[schema(value_type = Option<String>, example = "2024-12-31 23:59:59")]
+    pub end_date: Option<NaiveDateTime>,
     pub is_active: Option<bool>,
+    #[validate(length(max = 500, message = "must be at most 500 characters"))]
+    pub recurrence_rule: Option<String>, // "" untuk menghapus jadwal berulang
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub recurrence_duration_minutes: Option<i32>,
+    #[validate(length(max = 64, message = "must be at most 64 characters"))]
+    pub recurrence_timezone: Option<String>,
+    /// Ganti gambar dengan hasil `POST /api/banners/upload`
+    #[schema(value_type = Option<String>, format = "uuid")]
+    pub image_upload_id: Option<Uuid>,
 }
 
-#[derive(Serialize)]
+// Hanya nilai yang dikirim; pasangan CTA dan jadwal berulang terhadap nilai tersimpan
+// dicek di handler dengan FieldError yang sama
+fn validate_update_banner(req: &UpdateBannerRequest) -> Result<(), ValidationError> {
+    if let (Some(start), Some(end)) = (req.start_date, req.end_date)
+        && start >= end
+    {
+        return Err(cross_field("end_date", "must be after start_date"));
+    }
+    if req.cta_label.is_some() && req.cta_url.is_some() {
+        markdown::validate_cta(non_blank(&req.cta_label), non_blank(&req.cta_url))?;
+    } else if let Some(url) = non_blank(&req.cta_url) {
+        markdown::validate_cta_url(url)?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct BannerDto {
     pub id: i32,
     pub title: Option<String>,
-    pub content: String,
+    pub content: String, // sumber Markdown
+    pub content_html: Option<String>,
+    pub cta_label: Option<String>,
+    pub cta_url: Option<String>,
     pub image_url: Option<String>,
+    pub image_thumbnail_url: Option<String>,
+    pub image_medium_url: Option<String>,
+    pub image_webp_url: Option<String>,
     pub start_date: NaiveDateTime,
     pub end_date: NaiveDateTime,
     pub is_active: bool,
+    pub status: String,
+    pub recurrence_rule: Option<String>,
+    pub recurrence_duration_minutes: Option<i32>,
+    pub recurrence_timezone: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub locale: Option<String>, // locale terjemahan yang dipakai, None = konten asli
+}
+
+impl From<banner::Model> for BannerDto {
+    fn from(b: banner::Model) -> Self {
+        Self {
+            id: b.id,
+            title: b.title,
+            content: b.content,
+            content_html: b.content_html,
+            cta_label: b.cta_label,
+            cta_url: b.cta_url,
+            image_url: b.image_url,
+            image_thumbnail_url: b.image_thumbnail_url,
+            image_medium_url: b.image_medium_url,
+            image_webp_url: b.image_webp_url,
+            start_date: b.start_date,
+            end_date: b.end_date,
+            is_active: b.is_active,
+            status: b.status,
+            recurrence_rule: b.recurrence_rule,
+            recurrence_duration_minutes: b.recurrence_duration_minutes,
+            recurrence_timezone: b.recurrence_timezone,
+            created_at: b.created_at,
+            updated_at: b.updated_at,
+            deleted_at: b.deleted_at,
+            locale: None,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BannerImageDto {
+    /// Dipakai sebagai `image_upload_id` saat membuat / mengubah banner
+    #[schema(value_type = String, format = "uuid")]
+    pub upload_id: Uuid,
+    // null kalau URL gagal dibuat (mis. presign S3 error); penyebabnya dicatat di log
+    pub image_url: Option<String>,
+    pub image_thumbnail_url: Option<String>,
+    pub image_medium_url: Option<String>,
+    pub image_webp_url: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BannerRevisionDto {
+    pub id: i32,
+    pub banner_id: i32,
+    pub revision: i32,
+    pub action: String,
+    pub changed_by: Option<String>,
+    #[schema(value_type = Object)]
+    pub snapshot: serde_json::Value,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl From<banner_revision::Model> for BannerRevisionDto {
+    fn from(r: banner_revision::Model) -> Self {
+        Self {
+            id: r.id,
+            banner_id: r.banner_id,
+            revision: r.revision,
+            action: r.action,
+            changed_by: r.changed_by,
+            snapshot: r.snapshot,
+            created_at: r.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TransitionRequest {
+    pub comment: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BannerReviewDto {
+    pub id: i32,
+    pub banner_id: i32,
+    pub from_status: String,
+    pub to_status: String,
+    pub actor: String,
+    pub comment: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+impl From<banner_review::Model> for BannerReviewDto {
+    fn from(r: banner_review::Model) -> Self {
+        Self {
+            id: r.id,
+            banner_id: r.banner_id,
+            from_status: r.from_status,
+            to_status: r.to_status,
+            actor: r.actor,
+            comment: r.comment,
+            created_at: r.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct UpsertTranslationRequest {
+    #[validate(length(max = MAX_TITLE_LEN, message = "must be at most 255 characters"))]
+    pub title: Option<String>,
+    #[validate(
+        custom(function = "not_blank"),
+        length(max = MAX_CONTENT_LEN, message = "must be at most 10000 characters")
+    )]
+    pub content: String, // Markdown
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BannerTranslationDto {
+    pub id: i32,
+    pub banner_id: i32,
+    pub locale: String,
+    pub title: Option<String>,
+    pub content: String,
+    pub content_html: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+impl From<banner_translation::Model> for BannerTranslationDto {
+    fn from(t: banner_translation::Model) -> Self {
+        Self {
+            id: t.id,
+            banner_id: t.banner_id,
+            locale: t.locale,
+            title: t.title,
+            content: t.content,
+            content_html: t.content_html,
+            created_at: t.created_at,
+            updated_at: t.updated_at,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub events: Vec<String>,
+    pub secret: Option<String>, // kosong: dibuatkan otomatis
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct WebhookDto {
+    pub id: i32,
+    pub url: String,
+    pub events: Vec<String>,
+    pub is_active: bool,
+    // Secret hanya ditampilkan sekali, saat langganan dibuat
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub created_by: Option<String>,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
 }
+
+impl From<webhook_subscription::Model> for WebhookDto {
+    fn from(w: webhook_subscription::Model) -> Self {
+        Self {
+            id: w.id,
+            url: w.url,
+            events: w.events.split(',').map(|e| e.trim().to_string()).collect(),
+            is_active: w.is_active,
+            secret: None,
+            created_by: w.created_by,
+            created_at: w.created_at,
+            updated_at: w.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct WebhookDeliveryDto {
+    pub id: i32,
+    pub subscription_id: i32,
+    pub event_id: String,
+    pub event: String,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: Option<NaiveDateTime>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<NaiveDateTime>,
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+impl From<webhook_delivery::Model> for WebhookDeliveryDto {
+    fn from(d: webhook_delivery::Model) -> Self {
+        Self {
+            id: d.id,
+            subscription_id: d.subscription_id,
+            event_id: d.event_id,
+            event: d.event,
+            status: d.status,
+            attempts: d.attempts,
+            next_attempt_at: d.next_attempt_at,
+            last_status_code: d.last_status_code,
+            last_error: d.last_error,
+            delivered_at: d.delivered_at,
+            payload: serde_json::from_str(&d.payload).unwrap_or(serde_json::Value::String(d.payload)),
+            created_at: d.created_at,
+            updated_at: d.updated_at,
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use actix_web::HttpResponse;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi, ToSchema};
+
+use crate::{handlers, health, metrics};
+
+/// OpenAPI 3 document for every route in `routes::configure`.
+///
+/// Each handler carries its own `#[utoipa::path]`; a new handler must also be
+/// listed in `paths(...)` below, which the test at the bottom enforces.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Attendance & Banner API",
+        description = "Clock-in/clock-out attendance and announcement banners."
+    ),
+    paths(
+        openapi_json,
+        docs,
+        health::healthz,
+        health::readyz,
+        health::version,
+        metrics::metrics,
+        handlers::clock_in,
+        handlers::clock_out,
+        handlers::get_history,
+        handlers::presence_ws,
+        handlers::upload_banner_image,
+        handlers::create_banner,
+        handlers::get_banners,
+        handlers::get_active_banner,
+        handlers::stream_banners,
+        handlers::get_trash,
+        handlers::restore_banner,
+        handlers::submit_banner,
+        handlers::approve_banner,
+        handlers::reject_banner,
+        handlers::publish_banner,
+        handlers::archive_banner,
+        handlers::reopen_banner,
+        handlers::get_banner_reviews,
+        handlers::get_banner_translations,
+        handlers::upsert_banner_translation,
+        handlers::delete_banner_translation,
+        handlers::get_banner_revisions,
+        handlers::restore_banner_revision,
+        handlers::update_banner_image,
+        handlers::update_banner,
+        handlers::delete_banner,
+        handlers::create_webhook,
+        handlers::get_webhooks,
+        handlers::update_webhook,
+        handlers::delete_webhook,
+        handlers::ping_webhook,
+        handlers::get_webhook_deliveries,
+        handlers::retry_webhook_delivery,
+        handlers::run_media_gc,
+    ),
+    components(schemas(MessageBody, ValidationErrors, ImageUpload, CreateBannerForm)),
+    modifiers(&BearerAuth),
+    tags(
+        (name = "attendance", description = "Clock-in, clock-out and presence"),
+        (name = "banners", description = "Announcement banners"),
+        (name = "banner workflow", description = "Draft, review and publish"),
+        (name = "translations", description = "Localized banner content"),
+        (name = "revisions", description = "Banner history and rollback"),
+        (name = "webhooks", description = "Outbound webhooks (admin)"),
+        (name = "admin", description = "Maintenance (admin)"),
+        (name = "health", description = "Probes, metrics and build info"),
+        (name = "docs", description = "This documentation")
+    )
+)]
+pub struct ApiDoc;
+
+// Token dari API_TOKENS, dikirim sebagai `Authorization: Bearer <token>`
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+// Bentuk response tanpa `data`: semua error dan sukses tanpa payload
+#[derive(ToSchema)]
+#[allow(dead_code)] // hanya untuk dokumentasi
+pub struct MessageBody {
+    #[schema(example = "error")]
+    status: String,
+    message: String,
+    /// Only on errors; same value as the `X-Request-Id` response header
+    request_id: Option<String>,
+}
+
+// Response 422: peta error per field
+#[derive(ToSchema)]
+#[allow(dead_code)] // hanya untuk dokumentasi
+pub struct ValidationErrors {
+    #[schema(example = "error")]
+    status: String,
+    #[schema(example = "Validation failed")]
+    message: String,
+    #[schema(example = json!({"end_date": ["must be after start_date"]}))]
+    data: std::collections::BTreeMap<String, Vec<String>>,
+    request_id: Option<String>,
+}
+
+// Form multipart upload gambar banner
+#[derive(ToSchema)]
+#[allow(dead_code)] // hanya untuk dokumentasi
+pub struct ImageUpload {
+    /// JPEG, PNG atau WebP
+    #[schema(value_type = String, format = Binary)]
+    file: Vec<u8>,
+}
+
+// Form multipart untuk membuat banner sekaligus gambarnya
+#[derive(ToSchema)]
+#[allow(dead_code)] // hanya untuk dokumentasi
+pub struct CreateBannerForm {
+    /// CreateBannerRequest sebagai JSON
+    #[schema(example = r#"{"title":"Promo","content":"**Diskon**","start_date":"2024-01-01 10:00:00","end_date":"2024-12-31 23:59:59"}"#)]
+    data: String,
+    /// JPEG, PNG atau WebP (opsional)
+    #[schema(value_type = Option<String>, format = Binary)]
+    image: Option<Vec<u8>>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/openapi.json",
+    tag = "docs",
+    summary = "OpenAPI document",
+    responses(
+        (status = 200, description = "OpenAPI 3 JSON", content_type = "application/json", body = Object),
+    )
+)]
+pub async fn openapi_json() -> HttpResponse {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/docs",
+    tag = "docs",
+    summary = "Swagger UI",
+    responses(
+        (status = 200, description = "HTML page", content_type = "text/html", body = String),
+    )
+)]
+pub async fn docs() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(SWAGGER_UI)
+}
+
+const SWAGGER_UI: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>Attendance &amp; Banner API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js" crossorigin></script>
+  <script>
+    window.onload = () => {
+      window.ui = SwaggerUIBundle({ url: "/api/openapi.json", dom_id: "#swagger-ui" });
+    };
+  </script>
+</body>
+</html>
+"##;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    // (METHOD, path) dari source routes.rs: prefix web::scope berlaku sampai kurung penutupnya
+    fn configured_routes() -> BTreeSet<(String, String)> {
+        let source = include_str!("routes.rs");
+        let mut routes = BTreeSet::new();
+        let mut scopes: Vec<(usize, String)> = Vec::new();
+        let mut depth = 0usize;
+        let mut rest = source;
+
+        while let Some(c) = rest.chars().next() {
+            if let Some(after) = rest.strip_prefix("web::scope(\"") {
+                let end = after.find('"').expect("unterminated scope path");
+                scopes.push((depth, after[..end].to_string()));
+                rest = after[end + 1..].strip_prefix(')').expect("scope path must be a literal");
+                continue;
+            }
+            if let Some(after) = rest.strip_prefix(".route(") {
+                let after = after.trim_start();
+                let after = after.strip_prefix('"').expect("route path must be a literal");
+                let end = after.find('"').unwrap();
+                let path = &after[..end];
+                let method_start = after.find("web::").unwrap() + "web::".len();
+                let method_len = after[method_start..].find('(').unwrap();
+                let method = after[method_start..method_start + method_len].to_uppercase();
+                let prefix = scopes.last().map(|(_, p)| p.as_str()).unwrap_or("");
+                routes.insert((method, format!("{}{}", prefix, path)));
+                depth += 1;
+                rest = &after[end..];
+                continue;
+            }
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    while scopes.last().is_some_and(|(d, _)| *d > depth) {
+                        scopes.pop();
+                    }
+                }
+                _ => {}
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+        routes
+    }
+
+    fn documented_routes() -> BTreeSet<(String, String)> {
+        let doc = ApiDoc::openapi();
+        let mut routes = BTreeSet::new();
+        for (path, item) in doc.paths.paths {
+            let methods = [
+                ("GET", item.get.is_some()),
+                ("POST", item.post.is_some()),
+                ("PUT", item.put.is_some()),
+                ("DELETE", item.delete.is_some()),
+                ("PATCH", item.patch.is_some()),
+            ];
+            for (method, present) in methods {
+                if present {
+                    routes.insert((method.to_string(), path.clone()));
+                }
+            }
+        }
+        routes
+    }
+
+    #[test]
+    fn every_route_is_documented() {
+        let configured = configured_routes();
+        assert!(configured.len() > 30, "route parser found only {:?}", configured);
+        let documented = documented_routes();
+
+        let undocumented: Vec<_> = configured.difference(&documented).collect();
+        assert!(undocumented.is_empty(), "routes without an OpenAPI entry: {:?}", undocumented);
+
+        let stale: Vec<_> = documented.difference(&configured).collect();
+        assert!(stale.is_empty(), "OpenAPI entries without a route: {:?}", stale);
+    }
+}
@@ -0,0 +1,111 @@
+use chrono::NaiveDateTime;
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use crate::entity::attendance;
+
+// Kapasitas buffer event; koneksi yang tertinggal lebih jauh dikirimi snapshot ulang
+const CHANNEL_CAPACITY: usize = 256;
+
+// Sesi clock-in yang masih terbuka
+#[derive(Clone, Serialize)]
+pub struct Session {
+    pub attendance_id: i32,
+    pub user_id: String,
+    pub department: Option<String>,
+    pub clock_in_time: NaiveDateTime,
+}
+
+impl From<&attendance::Model> for Session {
+    fn from(a: &attendance::Model) -> Self {
+        Self {
+            attendance_id: a.id,
+            user_id: a.user_id.clone(),
+            department: a.department.clone(),
+            clock_in_time: a.clock_in_time,
+        }
+    }
+}
+
+/// Pesan JSON yang dikirim ke client WebSocket presence.
+#[derive(Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PresenceMessage {
+    Snapshot { sessions: Vec<Session> },
+    ClockIn { session: Session },
+    ClockOut { session: Session, clock_out_time: NaiveDateTime },
+}
+
+impl PresenceMessage {
+    // Snapshot selalu lolos, event hanya untuk departemen yang diminta
+    pub fn matches(&self, department: Option<&str>) -> bool {
+        match (self, department) {
+            (_, None) | (PresenceMessage::Snapshot { .. }, _) => true,
+            (
+                PresenceMessage::ClockIn { session } | PresenceMessage::ClockOut { session, .. },
+                Some(dept),
+            ) => session.department.as_deref() == Some(dept),
+        }
+    }
+}
+
+/// Broadcast clock-in/clock-out ke semua koneksi `/api/attendance/presence`.
+pub struct PresenceHub {
+    tx: broadcast::Sender<Arc<PresenceMessage>>,
+}
+
+impl Default for PresenceHub {
+    fn default() -> Self {
+        Self {
+            tx: broadcast::channel(CHANNEL_CAPACITY).0,
+        }
+    }
+}
+
+impl PresenceHub {
+    pub fn clock_in(&self, row: &attendance::Model) {
+        self.send(PresenceMessage::ClockIn {
+            session: Session::from(row),
+        });
+    }
+
+    pub fn clock_out(&self, row: &attendance::Model) {
+        if let Some(clock_out_time) = row.clock_out_time {
+            self.send(PresenceMessage::ClockOut {
+                session: Session::from(row),
+                clock_out_time,
+            });
+        }
+    }
+
+    fn send(&self, message: PresenceMessage) {
+        // Tidak ada subscriber bukan error
+        let _ = self.tx.send(Arc::new(message));
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<PresenceMessage>> {
+        self.tx.subscribe()
+    }
+}
+
+/// Semua sesi terbuka, opsional per departemen.
+///
+/// Dipanggil setelah `subscribe()`, jadi event yang terjadi di antaranya bisa
+/// muncul dua kali; client mengenali duplikat lewat `attendance_id`.
+pub async fn snapshot<C: ConnectionTrait>(
+    db: &C,
+    department: Option<&str>,
+) -> Result<PresenceMessage, DbErr> {
+    let mut find = attendance::Entity::find()
+        .filter(attendance::Column::ClockOutTime.is_null())
+        .order_by_asc(attendance::Column::ClockInTime);
+    if let Some(dept) = department {
+        find = find.filter(attendance::Column::Department.eq(dept));
+    }
+    let rows = find.all(db).await?;
+    Ok(PresenceMessage::Snapshot {
+        sessions: rows.iter().map(Session::from).collect(),
+    })
+}
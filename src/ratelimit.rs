@@ -0,0 +1,313 @@
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER};
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpMessage, HttpResponse, mime, web};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::auth::{self, ApiTokens};
+use crate::config::{RateLimitConfig, RatePolicy};
+use crate::metrics::Metrics;
+use crate::responses::ApiResponse;
+
+const RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+const RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+const RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+// Bucket yang sudah penuh lagi dibuang setiap sekian pemanggilan
+const SWEEP_EVERY: u64 = 1024;
+
+#[derive(Debug)]
+pub struct RateLimitError(pub String);
+
+impl fmt::Display for RateLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Hasil mengambil satu token dari bucket.
+#[derive(Clone, Copy, Debug)]
+pub struct Decision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    /// Sampai bucket penuh lagi.
+    pub reset: Duration,
+    /// Sampai satu token tersedia; nol kalau `allowed`.
+    pub retry_after: Duration,
+}
+
+/// Penyimpanan bucket rate limit. Implementasi bawaan di memori (per proses);
+/// untuk beberapa instance di belakang load balancer pasang store bersama, mis. Redis.
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Ambil satu token dari bucket `key`. Bucket yang belum ada mulai dalam keadaan penuh.
+    async fn take(&self, key: &str, policy: RatePolicy) -> Result<Decision, RateLimitError>;
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    full_at: Instant,
+}
+
+#[derive(Default)]
+pub struct MemoryStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+    calls: AtomicU64,
+}
+
+impl MemoryStore {
+    fn take_at(&self, key: &str, policy: RatePolicy, now: Instant) -> Decision {
+        let capacity = policy.burst as f64;
+        let per_sec = policy.per_minute as f64 / 60.0;
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if self.calls.fetch_add(1, Ordering::Relaxed) % SWEEP_EVERY == SWEEP_EVERY - 1 {
+            buckets.retain(|_, b| b.full_at > now);
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+            full_at: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_sec).min(capacity);
+        bucket.updated = now;
+
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+        let reset = Duration::from_secs_f64((capacity - bucket.tokens) / per_sec);
+        bucket.full_at = now + reset;
+
+        Decision {
+            allowed,
+            limit: policy.burst,
+            remaining: bucket.tokens.floor() as u32,
+            reset,
+            retry_after: if allowed {
+                Duration::ZERO
+            } else {
+                Duration::from_secs_f64((1.0 - bucket.tokens) / per_sec)
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryStore {
+    async fn take(&self, key: &str, policy: RatePolicy) -> Result<Decision, RateLimitError> {
+        Ok(self.take_at(key, policy, Instant::now()))
+    }
+}
+
+pub struct RateLimiter {
+    cfg: RateLimitConfig,
+    store: Arc<dyn RateLimitStore>,
+}
+
+impl RateLimiter {
+    pub fn new(cfg: RateLimitConfig, store: Arc<dyn RateLimitStore>) -> Self {
+        Self { cfg, store }
+    }
+
+    // Policy menurut pola route; probe, metrics dan file statis tidak dibatasi.
+    // Buat banner dengan form multipart ikut policy upload karena membawa gambar
+    fn policy_for(
+        &self,
+        method: &Method,
+        route: Option<&str>,
+        multipart: bool,
+    ) -> Option<(&'static str, RatePolicy)> {
+        match (method, route?) {
+            (&Method::POST, "/api/banners/upload") | (&Method::PUT, "/api/banners/{id}/image") => {
+                Some(("upload", self.cfg.upload))
+            }
+            (&Method::POST, "/api/banners") if multipart => Some(("upload", self.cfg.upload)),
+            (&Method::POST, "/api/attendance/clockin" | "/api/attendance/clockout") => {
+                Some(("attendance", self.cfg.attendance))
+            }
+            (_, route) if route.starts_with("/api/") => Some(("default", self.cfg.default)),
+            _ => None,
+        }
+    }
+
+    fn client_ip(&self, req: &ServiceRequest) -> String {
+        if self.cfg.trust_proxy {
+            req.connection_info()
+                .realip_remote_addr()
+                .unwrap_or("unknown")
+                .to_string()
+        } else {
+            req.peer_addr()
+                .map(|addr| addr.ip().to_string())
+                .unwrap_or_else(|| "unknown".to_string())
+        }
+    }
+
+    // Store yang error tidak boleh menjatuhkan API: request dilewatkan
+    async fn take(&self, key: &str, policy: RatePolicy) -> Option<Decision> {
+        match self.store.take(key, policy).await {
+            Ok(decision) => Some(decision),
+            Err(e) => {
+                tracing::warn!(key, error = %e, "rate limit store failed, allowing request");
+                None
+            }
+        }
+    }
+}
+
+/// Middleware rate limit (dipasang dengan `middleware::from_fn`).
+///
+/// Request ber-token dihitung per user, sisanya per IP. Token yang tidak dikenal
+/// juga mengurangi bucket `auth` milik IP tersebut untuk menahan tebak-tebakan token.
+pub async fn middleware(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let Some(limiter) = req.app_data::<web::Data<RateLimiter>>().cloned() else {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    };
+    let route = req.match_pattern();
+    let multipart = req
+        .mime_type()
+        .ok()
+        .flatten()
+        .is_some_and(|m| m.type_() == mime::MULTIPART && m.subtype() == mime::FORM_DATA);
+    let policy = match limiter.cfg.enabled {
+        true => limiter.policy_for(req.method(), route.as_deref(), multipart),
+        false => None,
+    };
+    let Some((name, policy)) = policy else {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    };
+
+    let ip = limiter.client_ip(&req);
+    let (client, bad_token) = match auth::bearer_token(req.request()) {
+        Some(token) => match req
+            .app_data::<web::Data<ApiTokens>>()
+            .and_then(|tokens| tokens.lookup(token))
+        {
+            Some(actor) => (format!("user:{}", actor.user_id), false),
+            None => (format!("ip:{}", ip), true),
+        },
+        None => (format!("ip:{}", ip), false),
+    };
+
+    if bad_token
+        && let Some(decision) = limiter.take(&format!("auth:ip:{}", ip), limiter.cfg.auth).await
+        && !decision.allowed
+    {
+        return Ok(too_many_requests(req, "auth", decision));
+    }
+
+    let Some(decision) = limiter.take(&format!("{}:{}", name, client), policy).await else {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    };
+    if !decision.allowed {
+        return Ok(too_many_requests(req, name, decision));
+    }
+
+    let mut res = next.call(req).await?;
+    insert_headers(res.headers_mut(), &decision);
+    Ok(res.map_into_left_body())
+}
+
+fn too_many_requests<B>(
+    req: ServiceRequest,
+    policy: &str,
+    decision: Decision,
+) -> ServiceResponse<EitherBody<B>> {
+    tracing::warn!(policy, "rate limit exceeded");
+    if let Some(metrics) = req.app_data::<web::Data<Metrics>>() {
+        metrics.record_rate_limited(policy);
+    }
+    let mut res = HttpResponse::TooManyRequests()
+        .insert_header((RETRY_AFTER, ceil_secs(decision.retry_after).max(1)))
+        .json(ApiResponse::<()>::error("Too many requests, retry later"));
+    insert_headers(res.headers_mut(), &decision);
+    req.into_response(res).map_into_right_body()
+}
+
+// Header RateLimit-* (draft IETF), nilai reset dalam detik
+fn insert_headers(headers: &mut HeaderMap, decision: &Decision) {
+    headers.insert(RATELIMIT_LIMIT, HeaderValue::from(decision.limit));
+    headers.insert(RATELIMIT_REMAINING, HeaderValue::from(decision.remaining));
+    headers.insert(RATELIMIT_RESET, HeaderValue::from(ceil_secs(decision.reset)));
+}
+
+fn ceil_secs(d: Duration) -> u64 {
+    d.as_secs_f64().ceil() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3 request burst, 1 token per detik
+    const POLICY: RatePolicy = RatePolicy::new(3, 60);
+
+    #[test]
+    fn denies_once_burst_is_exhausted() {
+        let store = MemoryStore::default();
+        let now = Instant::now();
+        for remaining in [2, 1, 0] {
+            let decision = store.take_at("ip:1", POLICY, now);
+            assert!(decision.allowed);
+            assert_eq!(decision.remaining, remaining);
+            assert_eq!(decision.retry_after, Duration::ZERO);
+        }
+        let denied = store.take_at("ip:1", POLICY, now);
+        assert!(!denied.allowed);
+        assert_eq!(denied.limit, 3);
+        assert_eq!(denied.remaining, 0);
+        assert_eq!(ceil_secs(denied.reset), 3);
+        // bucket lain tidak ikut habis
+        assert!(store.take_at("ip:2", POLICY, now).allowed);
+    }
+
+    #[test]
+    fn retry_after_counts_down_to_next_token() {
+        let store = MemoryStore::default();
+        let now = Instant::now();
+        for _ in 0..3 {
+            store.take_at("ip:1", POLICY, now);
+        }
+        let denied = store.take_at("ip:1", POLICY, now);
+        assert_eq!(denied.retry_after, Duration::from_secs(1));
+
+        let later = store.take_at("ip:1", POLICY, now + Duration::from_millis(400));
+        assert!(!later.allowed);
+        assert_eq!(later.retry_after.as_millis(), 600);
+        assert_eq!(ceil_secs(later.retry_after), 1);
+    }
+
+    #[test]
+    fn refills_after_the_period() {
+        let store = MemoryStore::default();
+        let now = Instant::now();
+        for _ in 0..4 {
+            store.take_at("ip:1", POLICY, now);
+        }
+
+        let one = store.take_at("ip:1", POLICY, now + Duration::from_secs(1));
+        assert!(one.allowed);
+        assert_eq!(one.remaining, 0);
+        assert!(!store.take_at("ip:1", POLICY, now + Duration::from_secs(1)).allowed);
+
+        // jauh setelah penuh, token tidak melebihi burst
+        let full = store.take_at("ip:1", POLICY, now + Duration::from_secs(60));
+        assert!(full.allowed);
+        assert_eq!(full.remaining, 2);
+    }
+}
@@ -1,6 +1,9 @@
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Serialize)]
+use crate::telemetry;
+
+#[derive(Serialize, ToSchema)]
 pub struct ApiResponse<T>
 where
     T: Serialize,
@@ -8,6 +11,9 @@ where
     pub status: String,
     pub message: String,
     pub data: Option<T>,
+    // Hanya di response error, sama dengan header X-Request-Id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl<T> ApiResponse<T>
@@ -19,6 +25,7 @@ where
             status: "success".to_string(),
             message: message.to_string(),
             data,
+            request_id: None,
         }
     }
 
@@ -27,6 +34,17 @@ where
             status: "error".to_string(),
             message: message.to_string(),
             data: None,
+            request_id: telemetry::current_request_id(),
+        }
+    }
+
+    // Error yang tetap membawa detail, mis. error per field atau hasil cek dependency
+    pub fn error_with(message: &str, data: T) -> Self {
+        Self {
+            status: "error".to_string(),
+            message: message.to_string(),
+            data: Some(data),
+            request_id: telemetry::current_request_id(),
         }
     }
 }
@@ -0,0 +1,164 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::auth::Actor;
+use crate::entity::{banner, banner_revision};
+
+pub const CREATED: &str = "created";
+pub const UPDATED: &str = "updated";
+pub const IMAGE_UPDATED: &str = "image_updated";
+pub const DELETED: &str = "deleted";
+pub const RESTORED: &str = "restored";
+pub const ROLLED_BACK: &str = "rolled_back";
+
+// Versi format snapshot; snapshot tanpa `version` (0) adalah dump banner::Model lama
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Isi banner yang disimpan di `banner_revision.snapshot`.
+///
+/// Sengaja bukan `banner::Model`: kolom baru di entity tidak boleh membuat revisi lama
+/// gagal dibaca. Nama field sama dengan kolom banner, jadi snapshot lama tetap cocok;
+/// field yang belum ada saat snapshot dibuat harus `Option` atau punya default.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BannerSnapshot {
+    #[serde(default)]
+    pub version: u32,
+    pub id: i32,
+    pub title: Option<String>,
+    pub content: String,
+    pub content_html: Option<String>,
+    pub cta_label: Option<String>,
+    pub cta_url: Option<String>,
+    pub image_url: Option<String>,
+    pub image_thumbnail_url: Option<String>,
+    pub image_medium_url: Option<String>,
+    pub image_webp_url: Option<String>,
+    pub start_date: NaiveDateTime,
+    pub end_date: NaiveDateTime,
+    pub is_active: bool,
+    pub status: Option<String>, // belum ada sebelum workflow review
+    pub recurrence_rule: Option<String>,
+    pub recurrence_duration_minutes: Option<i32>,
+    pub recurrence_timezone: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub expiry_notified_at: Option<NaiveDateTime>,
+}
+
+impl From<&banner::Model> for BannerSnapshot {
+    fn from(b: &banner::Model) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            id: b.id,
+            title: b.title.clone(),
+            content: b.content.clone(),
+            content_html: b.content_html.clone(),
+            cta_label: b.cta_label.clone(),
+            cta_url: b.cta_url.clone(),
+            image_url: b.image_url.clone(),
+            image_thumbnail_url: b.image_thumbnail_url.clone(),
+            image_medium_url: b.image_medium_url.clone(),
+            image_webp_url: b.image_webp_url.clone(),
+            start_date: b.start_date,
+            end_date: b.end_date,
+            is_active: b.is_active,
+            status: Some(b.status.clone()),
+            recurrence_rule: b.recurrence_rule.clone(),
+            recurrence_duration_minutes: b.recurrence_duration_minutes,
+            recurrence_timezone: b.recurrence_timezone.clone(),
+            created_at: b.created_at,
+            updated_at: b.updated_at,
+            deleted_at: b.deleted_at,
+            expiry_notified_at: b.expiry_notified_at,
+        }
+    }
+}
+
+impl BannerSnapshot {
+    // Key media gambar di snapshot, sama seperti media::banner_keys
+    pub fn image_keys(&self) -> Vec<String> {
+        [&self.image_url, &self.image_thumbnail_url, &self.image_medium_url, &self.image_webp_url]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect()
+    }
+}
+
+/// Store a full snapshot of `banner` as its next revision.
+///
+/// Call it with the same transaction as the write it describes, so history and
+/// the banner row never disagree.
+pub async fn record<C: ConnectionTrait>(
+    db: &C,
+    banner: &banner::Model,
+    action: &str,
+    actor: Option<&Actor>,
+) -> Result<banner_revision::Model, DbErr> {
+    let last = banner_revision::Entity::find()
+        .filter(banner_revision::Column::BannerId.eq(banner.id))
+        .order_by_desc(banner_revision::Column::Revision)
+        .one(db)
+        .await?;
+    let snapshot = serde_json::to_value(BannerSnapshot::from(banner))
+        .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+    banner_revision::ActiveModel {
+        banner_id: Set(banner.id),
+        revision: Set(last.map(|r| r.revision + 1).unwrap_or(1)),
+        action: Set(action.to_string()),
+        changed_by: Set(actor.map(|a| a.user_id.clone())),
+        snapshot: Set(snapshot),
+        created_at: Set(Some(Utc::now().naive_utc())),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+}
+
+// Baca kembali snapshot, termasuk dump banner::Model dari sebelum ada versi
+pub fn snapshot(rev: &banner_revision::Model) -> Result<BannerSnapshot, DbErr> {
+    serde_json::from_value(rev.snapshot.clone()).map_err(|e| DbErr::Custom(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revision(snapshot: serde_json::Value) -> banner_revision::Model {
+        banner_revision::Model {
+            id: 1,
+            banner_id: 7,
+            revision: 1,
+            action: CREATED.to_string(),
+            changed_by: None,
+            snapshot,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn reads_snapshot_from_before_status_column() {
+        let legacy = serde_json::json!({
+            "id": 7,
+            "title": "Promo",
+            "content": "Isi",
+            "image_url": "banners/a/original.jpg",
+            "start_date": "2024-01-01T00:00:00",
+            "end_date": "2024-02-01T00:00:00",
+            "is_active": true,
+            "created_at": null,
+            "updated_at": null,
+        });
+        let snapshot = snapshot(&revision(legacy)).expect("legacy snapshot");
+        assert_eq!(snapshot.version, 0);
+        assert_eq!(snapshot.status, None);
+        assert_eq!(snapshot.image_keys(), vec!["banners/a/original.jpg".to_string()]);
+    }
+}
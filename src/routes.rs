@@ -1,22 +1,93 @@
+use actix_web::dev::Service as _;
+use actix_web::http::Method;
 use actix_web::web;
 
+use crate::cache::ActiveBannerCache;
 use crate::handlers;
+use crate::health;
+use crate::metrics;
+use crate::openapi;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.service(
+    cfg.route("/healthz", web::get().to(health::healthz))
+    .route("/readyz", web::get().to(health::readyz))
+    .route("/version", web::get().to(health::version))
+    .route("/metrics", web::get().to(metrics::metrics))
+    .route("/api/openapi.json", web::get().to(openapi::openapi_json))
+    .route("/api/docs", web::get().to(openapi::docs))
+    .service(
         web::scope("/api/attendance")
             .route("/clockin", web::post().to(handlers::clock_in))
             .route("/clockout", web::post().to(handlers::clock_out))
+            .route("/presence", web::get().to(handlers::presence_ws))
             .route("", web::get().to(handlers::get_history)),
     )
     .service(
         web::scope("/api/banners")
+            // Setiap perubahan banner yang berhasil membuang cache banner aktif
+            .wrap_fn(|req, srv| {
+                let cache = match *req.method() {
+                    Method::GET | Method::HEAD => None,
+                    _ => req.app_data::<web::Data<ActiveBannerCache>>().cloned(),
+                };
+                let fut = srv.call(req);
+                async move {
+                    let res = fut.await?;
+                    if let Some(cache) = cache
+                        && res.status().is_success()
+                    {
+                        cache.invalidate();
+                    }
+                    Ok(res)
+                }
+            })
             .route("/upload", web::post().to(handlers::upload_banner_image))
             .route("", web::post().to(handlers::create_banner))
             .route("", web::get().to(handlers::get_banners))
             .route("/active", web::get().to(handlers::get_active_banner))
+            .route("/stream", web::get().to(handlers::stream_banners))
+            .route("/trash", web::get().to(handlers::get_trash))
+            .route("/{id}/restore", web::post().to(handlers::restore_banner))
+            .route("/{id}/submit", web::post().to(handlers::submit_banner))
+            .route("/{id}/approve", web::post().to(handlers::approve_banner))
+            .route("/{id}/reject", web::post().to(handlers::reject_banner))
+            .route("/{id}/publish", web::post().to(handlers::publish_banner))
+            .route("/{id}/archive", web::post().to(handlers::archive_banner))
+            .route("/{id}/reopen", web::post().to(handlers::reopen_banner))
+            .route("/{id}/reviews", web::get().to(handlers::get_banner_reviews))
+            .route("/{id}/translations", web::get().to(handlers::get_banner_translations))
+            .route(
+                "/{id}/translations/{locale}",
+                web::put().to(handlers::upsert_banner_translation),
+            )
+            .route(
+                "/{id}/translations/{locale}",
+                web::delete().to(handlers::delete_banner_translation),
+            )
+            .route("/{id}/revisions", web::get().to(handlers::get_banner_revisions))
+            .route(
+                "/{id}/revisions/{rev}/restore",
+                web::post().to(handlers::restore_banner_revision),
+            )
             .route("/{id}/image", web::put().to(handlers::update_banner_image))
             .route("/{id}", web::put().to(handlers::update_banner))
             .route("/{id}", web::delete().to(handlers::delete_banner)),
+    )
+    .service(
+        web::scope("/api/webhooks")
+            .route("", web::post().to(handlers::create_webhook))
+            .route("", web::get().to(handlers::get_webhooks))
+            .route("/{id}/ping", web::post().to(handlers::ping_webhook))
+            .route("/{id}/deliveries", web::get().to(handlers::get_webhook_deliveries))
+            .route(
+                "/{id}/deliveries/{delivery_id}/retry",
+                web::post().to(handlers::retry_webhook_delivery),
+            )
+            .route("/{id}", web::put().to(handlers::update_webhook))
+            .route("/{id}", web::delete().to(handlers::delete_webhook)),
+    )
+    .service(
+        web::scope("/api/admin")
+            .route("/media/gc", web::post().to(handlers::run_media_gc)),
     );
 }
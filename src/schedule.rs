@@ -0,0 +1,157 @@
+use chrono::{Duration, NaiveDateTime, TimeZone};
+use rrule::{RRule, RRuleSet, Tz, Unvalidated};
+
+use crate::entity::banner;
+use crate::validation::FieldError;
+
+// Cukup satu kemunculan untuk tahu apakah jendela sedang terbuka
+const MAX_OCCURRENCES: u16 = 1;
+
+/// Jadwal berulang sebuah banner: RRULE yang dimulai dari `start_date`, tiap
+/// kemunculan membuka jendela tampil selama `duration`.
+pub struct Recurrence {
+    set: RRuleSet,
+    tz: Tz,
+    duration: Duration,
+}
+
+impl Recurrence {
+    pub fn parse(
+        rule: &str,
+        duration_minutes: Option<i32>,
+        timezone: Option<&str>,
+        start_date: NaiveDateTime,
+    ) -> Result<Self, FieldError> {
+        let tz: Tz = match timezone {
+            Some(name) => name
+                .parse::<chrono_tz::Tz>()
+                .map_err(|_| FieldError::new("recurrence_timezone", "must be an IANA time zone, e.g. Asia/Jakarta"))?
+                .into(),
+            None => Tz::UTC,
+        };
+
+        let minutes = duration_minutes
+            .ok_or_else(|| FieldError::new("recurrence_duration_minutes", "is required with recurrence_rule"))?;
+        if minutes <= 0 {
+            return Err(FieldError::new("recurrence_duration_minutes", "must be positive"));
+        }
+
+        let invalid_rule = |e: rrule::RRuleError| FieldError::new("recurrence_rule", format!("is not a valid RRULE: {}", e));
+        let rule = rule.trim().trim_start_matches("RRULE:");
+        let rrule: RRule<Unvalidated> = rule.parse().map_err(invalid_rule)?;
+        let set = rrule
+            .build(tz.from_utc_datetime(&start_date))
+            .map_err(invalid_rule)?;
+
+        Ok(Self {
+            set,
+            tz,
+            duration: Duration::minutes(minutes as i64),
+        })
+    }
+
+    // Jendela yang mencakup `now`, sebagai (mulai, selesai) dalam UTC
+    pub fn window_at(&self, now: NaiveDateTime) -> Option<(NaiveDateTime, NaiveDateTime)> {
+        let now = self.tz.from_utc_datetime(&now);
+        let result = self
+            .set
+            .clone()
+            .after(now - self.duration)
+            .before(now)
+            .all(MAX_OCCURRENCES);
+        result.dates.first().and_then(|start| {
+            let end = *start + self.duration;
+            (end > now).then(|| (start.naive_utc(), end.naive_utc()))
+        })
+    }
+
+    // Awal jendela berikutnya yang dimulai setelah `after`, dalam UTC
+    pub fn next_start(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let after = self.tz.from_utc_datetime(&after);
+        let result = self.set.clone().after(after).all(2);
+        result
+            .dates
+            .into_iter()
+            .find(|start| *start > after)
+            .map(|start| start.naive_utc())
+    }
+}
+
+/// Apakah banner tampil pada `now` menurut jadwal berulangnya.
+///
+/// Banner tanpa `recurrence_rule` selalu tampil (batas start/end date dicek
+/// terpisah). Aturan yang tidak valid di database dianggap tidak tampil.
+pub fn is_visible(b: &banner::Model, now: NaiveDateTime) -> bool {
+    match recurrence_of(b) {
+        None => true,
+        Some(Ok(rec)) => rec.window_at(now).is_some(),
+        Some(Err(e)) => {
+            tracing::warn!(banner_id = b.id, error = %e, "Banner has invalid recurrence");
+            false
+        }
+    }
+}
+
+pub fn recurrence_of(b: &banner::Model) -> Option<Result<Recurrence, FieldError>> {
+    let rule = b.recurrence_rule.as_deref().filter(|r| !r.trim().is_empty())?;
+    Some(Recurrence::parse(
+        rule,
+        b.recurrence_duration_minutes,
+        b.recurrence_timezone.as_deref(),
+        b.start_date,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn window_is_open_only_within_duration() {
+        // Tiap hari 08:00 WIB (01:00 UTC) selama 90 menit
+        let rec = Recurrence::parse("FREQ=DAILY", Some(90), Some("Asia/Jakarta"), at(2026, 1, 1, 1, 0)).unwrap();
+
+        let window = Some((at(2026, 1, 5, 1, 0), at(2026, 1, 5, 2, 30)));
+        assert_eq!(rec.window_at(at(2026, 1, 5, 1, 0)), window);
+        assert_eq!(rec.window_at(at(2026, 1, 5, 2, 29)), window);
+        assert_eq!(rec.window_at(at(2026, 1, 5, 2, 30)), None);
+        assert_eq!(rec.window_at(at(2026, 1, 5, 0, 59)), None);
+        assert_eq!(rec.window_at(at(2026, 1, 5, 12, 0)), None);
+        // sebelum start_date belum ada kemunculan
+        assert_eq!(rec.window_at(at(2025, 12, 31, 1, 30)), None);
+        assert_eq!(rec.next_start(at(2026, 1, 5, 2, 0)), Some(at(2026, 1, 6, 1, 0)));
+    }
+
+    #[test]
+    fn window_follows_local_time_across_dst() {
+        // 09:00 waktu Amsterdam: CET (08:00 UTC) lalu CEST (07:00 UTC) setelah 29 Maret 2026
+        let rec = Recurrence::parse("FREQ=DAILY", Some(60), Some("Europe/Amsterdam"), at(2026, 3, 20, 8, 0)).unwrap();
+
+        assert_eq!(rec.window_at(at(2026, 3, 28, 8, 30)), Some((at(2026, 3, 28, 8, 0), at(2026, 3, 28, 9, 0))));
+        assert_eq!(rec.window_at(at(2026, 3, 28, 7, 30)), None);
+
+        assert_eq!(rec.window_at(at(2026, 3, 30, 7, 30)), Some((at(2026, 3, 30, 7, 0), at(2026, 3, 30, 8, 0))));
+        assert_eq!(rec.window_at(at(2026, 3, 30, 8, 30)), None);
+        assert_eq!(rec.next_start(at(2026, 3, 28, 9, 0)), Some(at(2026, 3, 29, 7, 0)));
+    }
+
+    #[test]
+    fn rejects_invalid_timezone_and_duration() {
+        let start = at(2026, 1, 1, 0, 0);
+        let err = Recurrence::parse("FREQ=DAILY", Some(60), Some("Asia/Jakart"), start).err().unwrap();
+        assert_eq!(err.field, "recurrence_timezone");
+
+        let err = Recurrence::parse("FREQ=DAILY", None, None, start).err().unwrap();
+        assert_eq!(err.field, "recurrence_duration_minutes");
+        let err = Recurrence::parse("FREQ=DAILY", Some(0), None, start).err().unwrap();
+        assert_eq!(err.field, "recurrence_duration_minutes");
+
+        let err = Recurrence::parse("FREQ=SOMETIMES", Some(60), None, start).err().unwrap();
+        assert_eq!(err.field, "recurrence_rule");
+    }
+}
@@ -0,0 +1,108 @@
+use actix_web::dev::ServerHandle;
+use std::future::Future;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+
+use crate::config::ShutdownConfig;
+
+/// Koordinasi shutdown antara server HTTP, koneksi streaming dan background job.
+///
+/// Dua tahap: `draining` dimulai begitu sinyal diterima (readiness 503, SSE/WebSocket
+/// ditutup supaya tidak menahan drain), `stopping` setelah server selesai drain
+/// (background job berhenti di antara dua putaran, bukan di tengah pekerjaan).
+#[derive(Clone, Default)]
+pub struct Shutdown {
+    draining: CancellationToken,
+    stopping: CancellationToken,
+    tasks: TaskTracker,
+}
+
+impl Shutdown {
+    pub fn is_draining(&self) -> bool {
+        self.draining.is_cancelled()
+    }
+
+    // Selesai saat drain dimulai; dipakai koneksi yang umurnya panjang
+    pub async fn draining(&self) {
+        self.draining.cancelled().await
+    }
+
+    // Selesai saat background job harus berhenti
+    pub async fn stopping(&self) {
+        self.stopping.cancelled().await
+    }
+
+    /// Jalankan background job yang ditunggu saat shutdown.
+    pub fn spawn<F>(&self, job: F)
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        actix_web::rt::spawn(self.tasks.track_future(job));
+    }
+
+    pub fn begin_drain(&self) {
+        self.draining.cancel();
+    }
+
+    /// Beri sinyal berhenti ke semua job lalu tunggu paling lama `timeout`.
+    /// `false` kalau masih ada job yang belum selesai.
+    pub async fn stop_jobs(&self, timeout: Duration) -> bool {
+        self.draining.cancel();
+        self.stopping.cancel();
+        self.tasks.close();
+        tokio::time::timeout(timeout, self.tasks.wait()).await.is_ok()
+    }
+}
+
+/// Tunggu SIGTERM/SIGINT lalu hentikan server dengan graceful.
+///
+/// Sinyal kedua selama drain menghentikan server saat itu juga.
+pub async fn watch_signals(server: ServerHandle, shutdown: Shutdown, cfg: ShutdownConfig) {
+    let signal = wait_for_signal().await;
+    tracing::info!(
+        signal,
+        delay_secs = cfg.readiness_delay_secs,
+        timeout_secs = cfg.timeout_secs,
+        "Shutdown requested, draining"
+    );
+    shutdown.begin_drain();
+
+    let graceful = async {
+        // Masih menerima koneksi selama load balancer belum melihat readiness 503
+        tokio::time::sleep(Duration::from_secs(cfg.readiness_delay_secs)).await;
+        server.stop(true).await;
+    };
+    tokio::select! {
+        _ = graceful => {}
+        signal = wait_for_signal() => {
+            tracing::warn!(signal, "Second signal received, stopping immediately");
+            server.stop(false).await;
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> &'static str {
+    use actix_web::rt::signal::unix::{SignalKind, signal};
+
+    let (Ok(mut term), Ok(mut int)) = (signal(SignalKind::terminate()), signal(SignalKind::interrupt())) else {
+        tracing::error!("Failed to install signal handlers");
+        return std::future::pending().await;
+    };
+    tokio::select! {
+        _ = term.recv() => "SIGTERM",
+        _ = int.recv() => "SIGINT",
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> &'static str {
+    match actix_web::rt::signal::ctrl_c().await {
+        Ok(()) => "ctrl-c",
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to install ctrl-c handler");
+            std::future::pending().await
+        }
+    }
+}
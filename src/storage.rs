@@ -0,0 +1,361 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use s3::bucket::Bucket;
+use s3::creds::Credentials;
+use s3::region::Region;
+use sha2::Sha256;
+use std::fmt;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct StorageError(pub String);
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Tempat penyimpanan file upload. Key berbentuk path relatif, mis. `banners/<uuid>/original.jpg`.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, key: &str, bytes: &[u8], content_type: &str) -> Result<(), StorageError>;
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+    /// URL yang bisa diakses client untuk key ini (bisa berupa presigned URL).
+    async fn url(&self, key: &str) -> Result<String, StorageError>;
+    /// Masa berlaku (detik) URL dari `url`; `None` kalau URL-nya permanen.
+    fn url_ttl(&self, key: &str) -> Option<u32>;
+}
+
+/// Resolve the value stored in an `image_*_url` column into a client URL.
+///
+/// Rows written before the blob store existed hold absolute URLs (`/uploads/...`
+/// or `http(s)://...`); those are returned unchanged, everything else is a key.
+/// A key whose URL cannot be built (e.g. a failed S3 presign) yields `None` and is logged.
+pub async fn resolve_url(store: &dyn BlobStore, stored: Option<String>) -> Option<String> {
+    let stored = stored?;
+    if is_url(&stored) {
+        return Some(stored);
+    }
+    match store.url(&stored).await {
+        Ok(url) => Some(url),
+        Err(e) => {
+            tracing::error!(key = %stored, error = %e, "Failed to build URL");
+            None
+        }
+    }
+}
+
+// Nilai lama yang sudah berupa URL, bukan key blob store
+pub fn is_url(stored: &str) -> bool {
+    stored.starts_with('/') || stored.starts_with("http://") || stored.starts_with("https://")
+}
+
+/// Kategori media privat: key dengan prefix tertentu hanya bisa diakses lewat URL bertanda tangan.
+///
+/// Local store menandatangani `/uploads/<key>?expires=..&signature=..` dengan HMAC-SHA256;
+/// S3 memakai presigned URL dengan TTL yang sama.
+#[derive(Clone, Default)]
+pub struct MediaAccess {
+    private_prefixes: Vec<String>,
+    secret: String,
+    ttl_secs: u32,
+}
+
+impl MediaAccess {
+    pub fn new(private_prefixes: Vec<String>, secret: String, ttl_secs: u32) -> Self {
+        Self {
+            private_prefixes,
+            secret,
+            ttl_secs,
+        }
+    }
+
+    pub fn is_private(&self, key: &str) -> bool {
+        self.private_prefixes.iter().any(|p| key.starts_with(p.as_str()))
+    }
+
+    pub fn ttl_secs(&self) -> u32 {
+        self.ttl_secs
+    }
+
+    // Query string untuk key privat, berlaku ttl_secs dari sekarang
+    pub fn signed_query(&self, key: &str) -> String {
+        let expires = Utc::now().timestamp() + self.ttl_secs as i64;
+        format!("expires={}&signature={}", expires, self.signature(key, expires))
+    }
+
+    /// Cek tanda tangan dan masa berlaku; perbandingan MAC dilakukan constant-time.
+    pub fn verify(&self, key: &str, expires: i64, signature: &str) -> bool {
+        if expires < Utc::now().timestamp() {
+            return false;
+        }
+        let Some(bytes) = decode_hex(signature) else {
+            return false;
+        };
+        self.mac(key, expires).verify_slice(&bytes).is_ok()
+    }
+
+    fn signature(&self, key: &str, expires: i64) -> String {
+        self.mac(key, expires)
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    fn mac(&self, key: &str, expires: i64) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(key.as_bytes());
+        mac.update(b"\n");
+        mac.update(expires.to_string().as_bytes());
+        mac
+    }
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+// Local filesystem, disajikan oleh uploads::serve di `public_url`
+pub struct LocalStore {
+    root: PathBuf,
+    public_url: String,
+    access: MediaAccess,
+}
+
+impl LocalStore {
+    pub fn new(root: impl Into<PathBuf>, public_url: &str, access: MediaAccess) -> Self {
+        Self {
+            root: root.into(),
+            public_url: public_url.trim_end_matches('/').to_string(),
+            access,
+        }
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalStore {
+    async fn put(&self, key: &str, bytes: &[u8], _content_type: &str) -> Result<(), StorageError> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| StorageError(format!("Failed to create upload directory: {}", e)))?;
+        }
+        // Tulis ke file sementara lalu rename, supaya tidak ada file setengah jadi di key aslinya.
+        // Nama diawali titik sehingga tidak pernah disajikan /uploads.
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("upload");
+        let tmp = path.with_file_name(format!(".{}.{}.tmp", file_name, Uuid::new_v4()));
+        let result = match tokio::fs::write(&tmp, bytes).await {
+            Ok(()) => tokio::fs::rename(&tmp, &path).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(StorageError(format!("Failed to write file: {}", e)));
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match tokio::fs::remove_file(self.root.join(key)).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(StorageError(format!("Failed to delete file: {}", e))),
+        }
+    }
+
+    async fn url(&self, key: &str) -> Result<String, StorageError> {
+        let url = format!("{}/{}", self.public_url, key);
+        if self.access.is_private(key) {
+            return Ok(format!("{}?{}", url, self.access.signed_query(key)));
+        }
+        Ok(url)
+    }
+
+    fn url_ttl(&self, key: &str) -> Option<u32> {
+        self.access.is_private(key).then(|| self.access.ttl_secs())
+    }
+}
+
+// S3-compatible (AWS S3, MinIO, dll)
+pub struct S3Store {
+    bucket: Box<Bucket>,
+    public_url: Option<String>,
+    presign_ttl_secs: Option<u32>,
+    access: MediaAccess,
+}
+
+pub struct S3Options {
+    pub bucket: String,
+    pub region: String,
+    pub endpoint: Option<String>,
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+    pub path_style: bool,
+    pub public_url: Option<String>,
+    pub presign_ttl_secs: Option<u32>,
+}
+
+impl S3Store {
+    pub fn new(opts: S3Options, access: MediaAccess) -> Result<Self, StorageError> {
+        let region = match opts.endpoint {
+            Some(endpoint) => Region::Custom {
+                region: opts.region,
+                endpoint,
+            },
+            None => opts
+                .region
+                .parse()
+                .map_err(|e| StorageError(format!("Invalid S3 region: {}", e)))?,
+        };
+        let credentials = Credentials::new(
+            opts.access_key.as_deref(),
+            opts.secret_key.as_deref(),
+            None,
+            None,
+            None,
+        )
+        .map_err(|e| StorageError(format!("Invalid S3 credentials: {}", e)))?;
+
+        let mut bucket = Bucket::new(&opts.bucket, region, credentials)
+            .map_err(|e| StorageError(format!("Invalid S3 bucket: {}", e)))?;
+        if opts.path_style {
+            bucket = bucket.with_path_style();
+        }
+
+        Ok(Self {
+            bucket,
+            public_url: opts.public_url.map(|u| u.trim_end_matches('/').to_string()),
+            presign_ttl_secs: opts.presign_ttl_secs,
+            access,
+        })
+    }
+}
+
+#[async_trait]
+impl BlobStore for S3Store {
+    async fn put(&self, key: &str, bytes: &[u8], content_type: &str) -> Result<(), StorageError> {
+        self.bucket
+            .put_object_with_content_type(key, bytes, content_type)
+            .await
+            .map(|_| ())
+            .map_err(|e| StorageError(format!("S3 put error: {}", e)))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        self.bucket
+            .delete_object(key)
+            .await
+            .map(|_| ())
+            .map_err(|e| StorageError(format!("S3 delete error: {}", e)))
+    }
+
+    async fn url(&self, key: &str) -> Result<String, StorageError> {
+        if let Some(ttl) = self.url_ttl(key) {
+            return self
+                .bucket
+                .presign_get(key, ttl, None)
+                .await
+                .map_err(|e| StorageError(format!("S3 presign error: {}", e)));
+        }
+        match &self.public_url {
+            Some(base) => Ok(format!("{}/{}", base, key)),
+            None => Ok(format!("{}/{}", self.bucket.url(), key)),
+        }
+    }
+
+    // Key privat selalu presigned, walau bucket punya URL publik
+    fn url_ttl(&self, key: &str) -> Option<u32> {
+        match self.access.is_private(key) {
+            true => Some(self.access.ttl_secs()),
+            false => self.presign_ttl_secs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "private/banners/abc/original.jpg";
+
+    fn access() -> MediaAccess {
+        MediaAccess::new(
+            vec!["private/".to_string()],
+            "0123456789abcdef0123456789abcdef".to_string(),
+            900,
+        )
+    }
+
+    fn future() -> i64 {
+        Utc::now().timestamp() + 600
+    }
+
+    #[test]
+    fn accepts_own_signature() {
+        let access = access();
+        let expires = future();
+        assert!(access.verify(KEY, expires, &access.signature(KEY, expires)));
+    }
+
+    #[test]
+    fn rejects_expired_signature() {
+        let access = access();
+        let expires = Utc::now().timestamp() - 1;
+        assert!(!access.verify(KEY, expires, &access.signature(KEY, expires)));
+    }
+
+    #[test]
+    fn rejects_tampered_signature_and_expiry() {
+        let access = access();
+        let expires = future();
+        let mut signature = access.signature(KEY, expires);
+        let last = if signature.ends_with('0') { "1" } else { "0" };
+        signature.replace_range(signature.len() - 1.., last);
+        assert!(!access.verify(KEY, expires, &signature));
+        // expires diperpanjang tanpa tanda tangan baru
+        assert!(!access.verify(KEY, expires + 3600, &access.signature(KEY, expires)));
+    }
+
+    #[test]
+    fn rejects_malformed_signatures() {
+        let access = access();
+        let expires = future();
+        let signature = access.signature(KEY, expires);
+        assert!(!access.verify(KEY, expires, &signature[1..])); // panjang ganjil
+        assert!(!access.verify(KEY, expires, &"zz".repeat(32)));
+        assert!(!access.verify(KEY, expires, &"é".repeat(32)));
+        assert!(!access.verify(KEY, expires, ""));
+    }
+
+    #[test]
+    fn rejects_signature_for_another_key() {
+        let access = access();
+        let expires = future();
+        let other = access.signature("private/banners/xyz/original.jpg", expires);
+        assert!(!access.verify(KEY, expires, &other));
+    }
+
+    #[test]
+    fn signed_query_round_trips() {
+        let access = access();
+        let query = access.signed_query(KEY);
+        let (expires, signature) = query
+            .strip_prefix("expires=")
+            .and_then(|q| q.split_once("&signature="))
+            .expect("expires & signature");
+        assert!(access.verify(KEY, expires.parse().unwrap(), signature));
+    }
+}
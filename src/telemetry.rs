@@ -0,0 +1,68 @@
+use actix_web::dev::ServiceRequest;
+use actix_web::http::header::HeaderName;
+use std::future::Future;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+use crate::config::LogConfig;
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+// Batas X-Request-Id dari client; di luar itu diganti id baru
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Pasang subscriber `tracing`. RUST_LOG, kalau di-set, menimpa `log.level`.
+///
+/// Record dari crate yang masih memakai `log` (actix, sqlx) ikut diteruskan.
+pub fn init(cfg: &LogConfig) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&cfg.level));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    if cfg.format == "json" {
+        builder
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .init();
+    } else {
+        builder.init();
+    }
+}
+
+// Pakai X-Request-Id dari client kalau wajar, selain itu buat baru
+pub fn request_id(req: &ServiceRequest) -> String {
+    req.headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID_LEN
+                && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+/// Jalankan `fut` (satu request) dengan `id` sebagai request id aktif.
+pub fn scope<F: Future>(id: String, fut: F) -> impl Future<Output = F::Output> {
+    REQUEST_ID.scope(id, fut)
+}
+
+/// Request id yang sedang diproses, None di luar request (mis. background job).
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+// Callback metrik SeaORM: satu event per query, tercatat di dalam span request yang aktif
+pub fn record_query(info: &sea_orm::metric::Info<'_>) {
+    let elapsed_ms = info.elapsed.as_secs_f64() * 1000.0;
+    if info.failed {
+        tracing::warn!(target: "db", elapsed_ms, sql = %info.statement.sql, "query failed");
+    } else {
+        tracing::debug!(target: "db", elapsed_ms, sql = %info.statement.sql, "query");
+    }
+}
@@ -0,0 +1,253 @@
+use actix_files::NamedFile;
+use actix_multipart::Multipart;
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use chrono::Utc;
+use futures_util::TryStreamExt as _;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Settings, UploadConfig};
+use crate::responses::ApiResponse;
+use crate::storage::MediaAccess;
+
+// Hanya tipe yang dihasilkan images::process yang disajikan; Content-Type dari sini, bukan tebakan
+const ALLOWED_TYPES: &[(&str, &str)] = &[
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("png", "image/png"),
+    ("webp", "image/webp"),
+];
+
+#[derive(Deserialize)]
+pub struct SignedQuery {
+    expires: Option<String>,
+    signature: Option<String>,
+}
+
+/// `GET /uploads/{key}`: file upload lokal tanpa directory listing.
+///
+/// Range, ETag dan Last-Modified ditangani `NamedFile`. Key di kategori privat
+/// (`storage.private_prefixes`) butuh `expires` + `signature` dari `BlobStore::url`.
+pub async fn serve(
+    req: HttpRequest,
+    key: web::Path<String>,
+    query: web::Query<SignedQuery>,
+    settings: web::Data<Settings>,
+    access: web::Data<MediaAccess>,
+) -> HttpResponse {
+    let key = key.into_inner();
+    let (Some(relative), Some(content_type)) = (safe_path(&key), content_type(&key)) else {
+        return not_found();
+    };
+
+    let private = access.is_private(&key);
+    let mut expires_at = None;
+    if private {
+        let expires = query.expires.as_deref().and_then(|e| e.parse::<i64>().ok());
+        match (expires, query.signature.as_deref()) {
+            (Some(expires), Some(signature)) if access.verify(&key, expires, signature) => {
+                expires_at = Some(expires);
+            }
+            _ => {
+                return HttpResponse::Forbidden()
+                    .json(ApiResponse::<()>::error("Invalid or expired signature"));
+            }
+        }
+    }
+
+    let path = Path::new(&settings.storage.upload_dir).join(relative);
+    let file = match NamedFile::open_async(&path).await {
+        Ok(file) if file.metadata().is_file() => file,
+        _ => return not_found(),
+    };
+    let mut res = file
+        .set_content_type(content_type.parse().expect("allow-listed content type"))
+        .use_etag(true)
+        .use_last_modified(true)
+        .respond_to(&req)
+        .map_into_boxed_body();
+
+    // URL bertanda tangan jangan disimpan cache bersama, dan tidak lebih lama dari masa berlakunya
+    if let Some(expires) = expires_at {
+        let max_age = (expires - Utc::now().timestamp()).max(0);
+        if let Ok(value) = HeaderValue::from_str(&format!("private, max-age={}", max_age)) {
+            res.headers_mut().insert(header::CACHE_CONTROL, value);
+        }
+    }
+    res
+}
+
+// Key relatif yang aman dipakai sebagai path: tanpa segmen kosong, `..`, file tersembunyi atau backslash
+fn safe_path(key: &str) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+    for segment in key.split('/') {
+        if segment.is_empty() || segment.starts_with('.') || segment.contains(['\\', '\0']) {
+            return None;
+        }
+        path.push(segment);
+    }
+    Some(path)
+}
+
+fn content_type(key: &str) -> Option<&'static str> {
+    let ext = Path::new(key).extension()?.to_str()?.to_ascii_lowercase();
+    ALLOWED_TYPES
+        .iter()
+        .find(|(allowed, _)| *allowed == ext)
+        .map(|(_, content_type)| *content_type)
+}
+
+fn not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(ApiResponse::<()>::error("File not found"))
+}
+
+#[derive(Debug)]
+pub enum UploadError {
+    FileTooLarge(usize),
+    RequestTooLarge(usize),
+    Malformed(String),
+    NoFile,
+}
+
+impl fmt::Display for UploadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UploadError::FileTooLarge(limit) => {
+                write!(f, "File exceeds maximum size of {} bytes", limit)
+            }
+            UploadError::RequestTooLarge(limit) => {
+                write!(f, "Request body exceeds maximum size of {} bytes", limit)
+            }
+            UploadError::Malformed(e) => write!(f, "Invalid multipart body: {}", e),
+            UploadError::NoFile => write!(f, "No file provided"),
+        }
+    }
+}
+
+impl UploadError {
+    // 413 untuk batas ukuran, 400 untuk body yang rusak atau tanpa file
+    pub fn response(&self) -> HttpResponse {
+        let body = ApiResponse::<()>::error(&self.to_string());
+        match self {
+            UploadError::FileTooLarge(_) | UploadError::RequestTooLarge(_) => {
+                HttpResponse::PayloadTooLarge().json(body)
+            }
+            UploadError::Malformed(_) | UploadError::NoFile => HttpResponse::BadRequest().json(body),
+        }
+    }
+}
+
+/// Isi form multipart: field teks dan (paling banyak) satu file.
+#[derive(Default)]
+pub struct UploadForm {
+    pub fields: HashMap<String, String>,
+    pub file: Option<Vec<u8>>,
+}
+
+/// Baca seluruh form multipart dengan batas dari `uploads`.
+///
+/// Content-Length yang sudah melewati batas ditolak sebelum body dibaca; untuk body
+/// chunked batas dicek per chunk, jadi upload besar berhenti di tengah jalan. Semua
+/// field dihitung ke batas per request. Error stream menjadi 400, bukan dianggap
+/// akhir input.
+pub async fn read_form(
+    req: &HttpRequest,
+    multipart: &mut Multipart,
+    limits: &UploadConfig,
+) -> Result<UploadForm, UploadError> {
+    let content_length = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if content_length.is_some_and(|len| len > limits.max_request_bytes) {
+        return Err(UploadError::RequestTooLarge(limits.max_request_bytes));
+    }
+
+    let malformed = |e: actix_multipart::MultipartError| UploadError::Malformed(e.to_string());
+    let mut form = UploadForm::default();
+    let mut total = 0;
+    while let Some(mut field) = multipart.try_next().await.map_err(malformed)? {
+        // Field dengan filename dianggap file, sisanya teks
+        let is_file = field
+            .content_disposition()
+            .and_then(|cd| cd.get_filename())
+            .is_some();
+        if is_file && form.file.is_some() {
+            return Err(UploadError::Malformed("only one file is allowed".to_string()));
+        }
+        let name = field.name().unwrap_or_default().to_string();
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.try_next().await.map_err(malformed)? {
+            total += chunk.len();
+            if total > limits.max_request_bytes {
+                return Err(UploadError::RequestTooLarge(limits.max_request_bytes));
+            }
+            if is_file && bytes.len() + chunk.len() > limits.max_file_bytes {
+                return Err(UploadError::FileTooLarge(limits.max_file_bytes));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        if is_file {
+            form.file = Some(bytes);
+        } else {
+            let text = String::from_utf8(bytes)
+                .map_err(|_| UploadError::Malformed(format!("field {} is not valid UTF-8", name)))?;
+            form.fields.insert(name, text);
+        }
+    }
+    Ok(form)
+}
+
+// Form yang wajib berisi file, mis. /api/banners/upload
+pub async fn read_file(
+    req: &HttpRequest,
+    multipart: &mut Multipart,
+    limits: &UploadConfig,
+) -> Result<Vec<u8>, UploadError> {
+    read_form(req, multipart, limits).await?.file.ok_or(UploadError::NoFile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safe_path_accepts_nested_keys() {
+        assert_eq!(
+            safe_path("banners/abc/original.jpg"),
+            Some(PathBuf::from("banners/abc/original.jpg"))
+        );
+    }
+
+    #[test]
+    fn safe_path_rejects_traversal_absolute_and_hidden() {
+        for key in [
+            "../etc/passwd",
+            "banners/../../secret.jpg",
+            "banners/./a.jpg",
+            "/etc/passwd",
+            "banners//a.jpg",
+            "banners/.a.jpg.tmp",
+            ".env",
+            "banners\\..\\a.jpg",
+            "banners/a\0.jpg",
+            "",
+        ] {
+            assert_eq!(safe_path(key), None, "{:?}", key);
+        }
+    }
+
+    #[test]
+    fn only_allow_listed_types_are_served() {
+        assert_eq!(content_type("banners/a/original.JPG"), Some("image/jpeg"));
+        assert_eq!(content_type("banners/a/original.webp"), Some("image/webp"));
+        assert_eq!(content_type("banners/a/index.html"), None);
+        assert_eq!(content_type("banners/a/noext"), None);
+    }
+}
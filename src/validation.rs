@@ -0,0 +1,291 @@
+use actix_multipart::Multipart;
+use actix_web::dev::Payload;
+use actix_web::error::{InternalError, JsonPayloadError, PathError, QueryPayloadError};
+use actix_web::http::StatusCode;
+use actix_web::{FromRequest, HttpMessage, HttpRequest, HttpResponse, mime, web};
+use chrono::NaiveDateTime;
+use futures_util::future::LocalBoxFuture;
+use serde::de::{self, DeserializeOwned};
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Deref;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::responses::ApiResponse;
+
+pub const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Pesan error per field, mis. `{"end_date": ["must be after start_date"]}`.
+pub type FieldErrors = BTreeMap<String, Vec<String>>;
+
+// 422 dengan peta error per field di dalam envelope standar
+pub fn error_response(errors: FieldErrors) -> HttpResponse {
+    HttpResponse::UnprocessableEntity().json(ApiResponse::error_with("Validation failed", errors))
+}
+
+pub fn field_error(field: &str, message: &str) -> HttpResponse {
+    error_response(BTreeMap::from([(field.to_string(), vec![message.to_string()])]))
+}
+
+fn is_json(m: &mime::Mime) -> bool {
+    m.subtype() == mime::JSON || m.suffix() == Some(mime::JSON)
+}
+
+fn envelope_error(status: StatusCode, message: &str) -> HttpResponse {
+    HttpResponse::build(status).json(ApiResponse::<()>::error(message))
+}
+
+/// Body JSON yang sudah dideserialisasi dan lolos `Validate`.
+///
+/// Berbeda dengan `web::Json`, error deserialisasi membawa path field-nya
+/// (mis. `start_date`), jadi client tahu field mana yang salah.
+pub struct ValidJson<T>(pub T);
+
+impl<T> Deref for ValidJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> FromRequest for ValidJson<T>
+where
+    T: DeserializeOwned + Validate + 'static,
+{
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let is_json = req.mime_type().ok().flatten().is_some_and(|m| is_json(&m));
+        let body = web::Bytes::from_request(req, payload);
+
+        Box::pin(async move {
+            if !is_json {
+                let resp = envelope_error(
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    "Content-Type must be application/json",
+                );
+                return Err(InternalError::from_response("content type", resp).into());
+            }
+            let body = body.await.map_err(|e| {
+                let resp = envelope_error(e.as_response_error().status_code(), &e.to_string());
+                InternalError::from_response(e, resp)
+            })?;
+
+            parse_json(&body).map(ValidJson).map_err(|errors| {
+                InternalError::from_response("validation", error_response(errors)).into()
+            })
+        })
+    }
+}
+
+/// Deserialisasi + `validate()` seperti `ValidJson`, untuk JSON yang tidak datang
+/// sebagai body, mis. field `data` di form multipart. Kirim error-nya lewat `error_response`.
+pub fn parse_json<T>(body: &[u8]) -> Result<T, FieldErrors>
+where
+    T: DeserializeOwned + Validate,
+{
+    let de = &mut serde_json::Deserializer::from_slice(body);
+    let value: T = serde_path_to_error::deserialize(de).map_err(|e| {
+        let path = e.path().to_string();
+        json_field_errors(Some(&path), e.inner())
+    })?;
+    value.validate().map_err(|e| validation_errors(&e))?;
+    Ok(value)
+}
+
+/// Body JSON (`ValidJson`) atau `multipart/form-data`, dipilih dari Content-Type.
+///
+/// Form multipart dibaca handler sendiri (lihat `uploads::read_form`), biasanya
+/// dengan field `data` berisi JSON yang sama ditambah file.
+pub enum JsonOrMultipart<T> {
+    Json(T),
+    Multipart(Multipart),
+}
+
+impl<T> FromRequest for JsonOrMultipart<T>
+where
+    T: DeserializeOwned + Validate + 'static,
+{
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let mime = req.mime_type().ok().flatten();
+        if mime.as_ref().is_some_and(|m| m.type_() == mime::MULTIPART && m.subtype() == mime::FORM_DATA) {
+            let multipart = Multipart::from_request(req, payload);
+            return Box::pin(async move { multipart.await.map(JsonOrMultipart::Multipart) });
+        }
+        if !mime.as_ref().is_some_and(is_json) {
+            let resp = envelope_error(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "Content-Type must be application/json or multipart/form-data",
+            );
+            return Box::pin(async move { Err(InternalError::from_response("content type", resp).into()) });
+        }
+        let json = ValidJson::<T>::from_request(req, payload);
+        Box::pin(async move { json.await.map(|v| JsonOrMultipart::Json(v.0)) })
+    }
+}
+
+/// Flatten `ValidationErrors` ke `FieldErrors`.
+///
+/// Error level struct (`#[validate(schema(...))]`) tersimpan di `__all__`;
+/// kodenya kita isi nama field yang dimaksud, jadi dipetakan ke field itu.
+pub fn validation_errors(errors: &ValidationErrors) -> FieldErrors {
+    let mut out = FieldErrors::new();
+    for (field, errs) in errors.field_errors() {
+        for e in errs {
+            let key = if field == "__all__" { e.code.to_string() } else { field.to_string() };
+            let message = e
+                .message
+                .as_ref()
+                .map(|m| m.to_string())
+                .unwrap_or_else(|| e.code.to_string());
+            out.entry(key).or_default().push(message);
+        }
+    }
+    out
+}
+
+// Error serde_json -> FieldErrors. `path` "." berarti root object.
+fn json_field_errors(path: Option<&str>, e: &serde_json::Error) -> FieldErrors {
+    use serde_json::error::Category;
+
+    let (field, message) = match e.classify() {
+        Category::Syntax | Category::Eof | Category::Io => {
+            ("body".to_string(), "malformed JSON".to_string())
+        }
+        Category::Data => {
+            // Pesan serde_json diakhiri " at line X column Y"
+            let full = e.to_string();
+            let msg = full.split(" at line ").next().unwrap_or(&full).to_string();
+            match (backticked(&msg, "unknown field `"), backticked(&msg, "missing field `")) {
+                (Some(name), _) => (name, "unknown field".to_string()),
+                (_, Some(name)) => (name, "is required".to_string()),
+                _ => match path {
+                    Some(p) if p != "." => (p.to_string(), msg),
+                    _ => ("body".to_string(), msg),
+                },
+            }
+        }
+    };
+    BTreeMap::from([(field, vec![message])])
+}
+
+fn backticked(msg: &str, prefix: &str) -> Option<String> {
+    let rest = msg.strip_prefix(prefix)?;
+    rest.find('`').map(|end| rest[..end].to_string())
+}
+
+/// Error handler untuk `web::Json` (dipasang lewat `JsonConfig`).
+pub fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default().error_handler(|err, _req| {
+        let resp = match &err {
+            JsonPayloadError::Deserialize(e) => error_response(json_field_errors(None, e)),
+            JsonPayloadError::ContentType => envelope_error(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "Content-Type must be application/json",
+            ),
+            JsonPayloadError::Overflow { .. } | JsonPayloadError::OverflowKnownLength { .. } => {
+                envelope_error(StatusCode::PAYLOAD_TOO_LARGE, &err.to_string())
+            }
+            _ => envelope_error(StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+        InternalError::from_response(err, resp).into()
+    })
+}
+
+/// Error handler untuk `web::Query`.
+pub fn query_config() -> web::QueryConfig {
+    web::QueryConfig::default().error_handler(|err, _req| {
+        let QueryPayloadError::Deserialize(e) = &err else {
+            let resp = envelope_error(StatusCode::BAD_REQUEST, &err.to_string());
+            return InternalError::from_response(err, resp).into();
+        };
+        let msg = e.to_string();
+        let field = backticked(&msg, "missing field `").unwrap_or_else(|| "query".to_string());
+        let resp = error_response(BTreeMap::from([(field, vec![msg])]));
+        InternalError::from_response(err, resp).into()
+    })
+}
+
+/// Error handler untuk `web::Path`, mis. `/api/banners/abc`.
+pub fn path_config() -> web::PathConfig {
+    web::PathConfig::default().error_handler(|err, _req| {
+        let PathError::Deserialize(e) = &err else {
+            let resp = envelope_error(StatusCode::BAD_REQUEST, &err.to_string());
+            return InternalError::from_response(err, resp).into();
+        };
+        let resp = error_response(BTreeMap::from([("path".to_string(), vec![e.to_string()])]));
+        InternalError::from_response(err, resp).into()
+    })
+}
+
+// Tanggal dalam format DATETIME_FORMAT, dipakai lewat `deserialize_with`
+pub fn datetime<'de, D: Deserializer<'de>>(d: D) -> Result<NaiveDateTime, D::Error> {
+    let s = String::deserialize(d)?;
+    NaiveDateTime::parse_from_str(&s, DATETIME_FORMAT)
+        .map_err(|_| de::Error::custom("invalid format, use YYYY-MM-DD HH:MM:SS"))
+}
+
+pub fn optional_datetime<'de, D: Deserializer<'de>>(d: D) -> Result<Option<NaiveDateTime>, D::Error> {
+    match Option::<String>::deserialize(d)? {
+        Some(s) => NaiveDateTime::parse_from_str(&s, DATETIME_FORMAT)
+            .map(Some)
+            .map_err(|_| de::Error::custom("invalid format, use YYYY-MM-DD HH:MM:SS")),
+        None => Ok(None),
+    }
+}
+
+// Custom validator: string tidak boleh kosong atau hanya spasi
+pub fn not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new("blank").with_message("must not be blank".into()));
+    }
+    Ok(())
+}
+
+// Error cross-field: kode = field yang ditandai (lihat `validation_errors`)
+pub fn cross_field(field: &'static str, message: impl Into<Cow<'static, str>>) -> ValidationError {
+    ValidationError::new(field).with_message(message.into())
+}
+
+/// Error satu field dari aturan yang melibatkan beberapa nilai (pasangan CTA, jadwal berulang).
+///
+/// Dipakai di validator struct (lewat `From` ke `ValidationError`) maupun di handler yang
+/// mengecek terhadap nilai tersimpan, jadi keduanya menghasilkan 422 dengan bentuk yang sama.
+#[derive(Debug, PartialEq)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+
+    pub fn response(&self) -> HttpResponse {
+        field_error(self.field, &self.message)
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.field, self.message)
+    }
+}
+
+impl From<FieldError> for ValidationError {
+    fn from(e: FieldError) -> Self {
+        cross_field(e.field, e.message)
+    }
+}
+
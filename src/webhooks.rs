@@ -0,0 +1,379 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use hmac::{Hmac, Mac};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::config::WebhookConfig;
+use crate::entity::{attendance, banner, webhook_delivery, webhook_subscription};
+use crate::workflow::BannerStatus;
+
+// Event yang bisa dilanggan
+pub const CLOCK_IN: &str = "attendance.clock_in";
+pub const CLOCK_OUT: &str = "attendance.clock_out";
+pub const BANNER_PUBLISHED: &str = "banner.published";
+pub const BANNER_EXPIRED: &str = "banner.expired";
+pub const EVENTS: &[&str] = &[CLOCK_IN, CLOCK_OUT, BANNER_PUBLISHED, BANNER_EXPIRED];
+
+// Dikirim lewat endpoint ping, hanya ke satu langganan
+pub const PING: &str = "webhook.ping";
+
+// Status baris webhook_delivery
+pub const PENDING: &str = "pending";
+pub const DELIVERED: &str = "delivered";
+pub const FAILED: &str = "failed";
+
+const BATCH_SIZE: u64 = 50;
+const MAX_ERROR_LEN: usize = 500;
+
+pub fn subscribes_to(sub: &webhook_subscription::Model, event: &str) -> bool {
+    sub.events.split(',').map(str::trim).any(|e| e == "*" || e == event)
+}
+
+/// Queue `event` for every active subscription that wants it.
+///
+/// Call this with the same transaction as the write that caused the event, so
+/// a delivery exists if and only if the change was committed.
+pub async fn enqueue<C: ConnectionTrait>(db: &C, event: &str, data: Value) -> Result<usize, DbErr> {
+    let subscriptions: Vec<webhook_subscription::Model> = webhook_subscription::Entity::find()
+        .filter(webhook_subscription::Column::IsActive.eq(true))
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|s| subscribes_to(s, event))
+        .collect();
+    if subscriptions.is_empty() {
+        return Ok(0);
+    }
+
+    let event_id = Uuid::new_v4().to_string();
+    let payload = envelope(&event_id, event, data);
+    for sub in &subscriptions {
+        insert_delivery(db, sub.id, &event_id, event, &payload).await?;
+    }
+    Ok(subscriptions.len())
+}
+
+// URL harus http(s) absolut dan event harus dikenal ("*" untuk semua)
+pub fn validate(url: &str, events: &[String]) -> Result<(), String> {
+    match url::Url::parse(url) {
+        Ok(u) if u.scheme() == "http" || u.scheme() == "https" => {}
+        _ => return Err("url must be an absolute http or https URL".to_string()),
+    }
+    if events.is_empty() {
+        return Err("events must not be empty".to_string());
+    }
+    if let Some(unknown) = events.iter().find(|e| *e != "*" && !EVENTS.contains(&e.as_str())) {
+        return Err(format!(
+            "Unknown event: {}. Allowed: {}",
+            unknown,
+            EVENTS.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+// Antrekan event ke satu langganan saja (ping)
+pub async fn enqueue_for<C: ConnectionTrait>(
+    db: &C,
+    subscription_id: i32,
+    event: &str,
+    data: Value,
+) -> Result<webhook_delivery::Model, DbErr> {
+    let event_id = Uuid::new_v4().to_string();
+    let payload = envelope(&event_id, event, data);
+    insert_delivery(db, subscription_id, &event_id, event, &payload).await
+}
+
+fn envelope(event_id: &str, event: &str, data: Value) -> String {
+    json!({
+        "id": event_id,
+        "event": event,
+        "created_at": Utc::now().naive_utc(),
+        "data": data,
+    })
+    .to_string()
+}
+
+async fn insert_delivery<C: ConnectionTrait>(
+    db: &C,
+    subscription_id: i32,
+    event_id: &str,
+    event: &str,
+    payload: &str,
+) -> Result<webhook_delivery::Model, DbErr> {
+    let now = Utc::now().naive_utc();
+    webhook_delivery::ActiveModel {
+        subscription_id: Set(subscription_id),
+        event_id: Set(event_id.to_string()),
+        event: Set(event.to_string()),
+        payload: Set(payload.to_string()),
+        status: Set(PENDING.to_string()),
+        attempts: Set(0),
+        next_attempt_at: Set(Some(now)),
+        created_at: Set(Some(now)),
+        updated_at: Set(Some(now)),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+}
+
+// Isi `data` untuk event attendance
+pub fn attendance_data(a: &attendance::Model) -> Value {
+    json!({
+        "id": a.id,
+        "user_id": a.user_id,
+        "department": a.department,
+        "clock_in_time": a.clock_in_time,
+        "clock_out_time": a.clock_out_time,
+    })
+}
+
+// Isi `data` untuk event banner
+pub fn banner_data(b: &banner::Model) -> Value {
+    json!({
+        "id": b.id,
+        "title": b.title,
+        "status": b.status,
+        "start_date": b.start_date,
+        "end_date": b.end_date,
+        "cta_label": b.cta_label,
+        "cta_url": b.cta_url,
+    })
+}
+
+/// `X-Webhook-Signature` value: HMAC-SHA256 over `"{timestamp}.{body}"`.
+///
+/// Receivers recompute it with the subscription secret and should reject
+/// timestamps too far from their own clock to stop replays.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    let hex: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("sha256={}", hex)
+}
+
+// Jeda sebelum percobaan berikutnya: base * 2^(attempts-1), dibatasi max
+pub fn backoff(attempts: i32, cfg: &WebhookConfig) -> Duration {
+    let exp = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    let secs = cfg.backoff_base_secs.saturating_mul(1i64 << exp);
+    Duration::seconds(secs.min(cfg.backoff_max_secs))
+}
+
+// Awal body respons untuk last_error; sisanya tidak dibaca supaya receiver
+// yang mengirim body besar tidak menahan atau memenuhi memori dispatcher
+async fn read_prefix(mut resp: reqwest::Response, limit: usize) -> String {
+    let mut buf = Vec::new();
+    while buf.len() < limit {
+        match resp.chunk().await {
+            Ok(Some(chunk)) => buf.extend_from_slice(&chunk),
+            Ok(None) | Err(_) => break,
+        }
+    }
+    buf.truncate(limit);
+    String::from_utf8_lossy(&buf).into_owned()
+}
+
+/// Send every delivery that is due. Returns how many were attempted.
+pub async fn dispatch_due(
+    db: &DatabaseConnection,
+    client: &reqwest::Client,
+    cfg: &WebhookConfig,
+) -> Result<usize, DbErr> {
+    let now = Utc::now().naive_utc();
+    let due = webhook_delivery::Entity::find()
+        .filter(webhook_delivery::Column::Status.eq(PENDING))
+        .filter(webhook_delivery::Column::NextAttemptAt.lte(now))
+        .order_by_asc(webhook_delivery::Column::NextAttemptAt)
+        .limit(BATCH_SIZE)
+        .all(db)
+        .await?;
+
+    let mut attempted = 0;
+    for delivery in due {
+        if !claim(db, &delivery, now, cfg).await? {
+            continue;
+        }
+        attempt(db, client, cfg, delivery).await?;
+        attempted += 1;
+    }
+    Ok(attempted)
+}
+
+// Geser next_attempt_at melewati timeout supaya instance lain tidak mengirim baris yang sama
+async fn claim(
+    db: &DatabaseConnection,
+    delivery: &webhook_delivery::Model,
+    now: NaiveDateTime,
+    cfg: &WebhookConfig,
+) -> Result<bool, DbErr> {
+    let lease = now + Duration::seconds(cfg.timeout_secs as i64 * 2 + 30);
+    let res = webhook_delivery::Entity::update_many()
+        .col_expr(webhook_delivery::Column::NextAttemptAt, Expr::value(lease))
+        .filter(webhook_delivery::Column::Id.eq(delivery.id))
+        .filter(webhook_delivery::Column::Status.eq(PENDING))
+        .filter(webhook_delivery::Column::NextAttemptAt.eq(delivery.next_attempt_at))
+        .exec(db)
+        .await?;
+    Ok(res.rows_affected == 1)
+}
+
+async fn attempt(
+    db: &DatabaseConnection,
+    client: &reqwest::Client,
+    cfg: &WebhookConfig,
+    delivery: webhook_delivery::Model,
+) -> Result<(), DbErr> {
+    let subscription = webhook_subscription::Entity::find_by_id(delivery.subscription_id)
+        .one(db)
+        .await?;
+    let attempts = delivery.attempts + 1;
+    let now = Utc::now().naive_utc();
+    let mut active: webhook_delivery::ActiveModel = delivery.clone().into();
+    active.attempts = Set(attempts);
+    active.updated_at = Set(Some(now));
+
+    let sub = match subscription {
+        Some(sub) if sub.is_active => sub,
+        _ => {
+            active.status = Set(FAILED.to_string());
+            active.next_attempt_at = Set(None);
+            active.last_error = Set(Some("Subscription deleted or disabled".to_string()));
+            active.update(db).await?;
+            return Ok(());
+        }
+    };
+
+    let timestamp = now.and_utc().timestamp();
+    let result = client
+        .post(&sub.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-Webhook-Id", delivery.event_id.as_str())
+        .header("X-Webhook-Event", delivery.event.as_str())
+        .header("X-Webhook-Delivery", delivery.id.to_string())
+        .header("X-Webhook-Timestamp", timestamp.to_string())
+        .header("X-Webhook-Signature", sign(&sub.secret, timestamp, &delivery.payload))
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+
+    let error = match result {
+        Ok(resp) => {
+            let status = resp.status();
+            active.last_status_code = Set(Some(status.as_u16() as i32));
+            if status.is_success() {
+                None
+            } else {
+                let body = read_prefix(resp, MAX_ERROR_LEN).await;
+                Some(format!("HTTP {}: {}", status.as_u16(), body))
+            }
+        }
+        Err(e) => {
+            active.last_status_code = Set(None);
+            Some(e.to_string())
+        }
+    };
+
+    match error {
+        None => {
+            active.status = Set(DELIVERED.to_string());
+            active.next_attempt_at = Set(None);
+            active.last_error = Set(None);
+            active.delivered_at = Set(Some(Utc::now().naive_utc()));
+        }
+        Some(e) => {
+            let e: String = e.chars().take(MAX_ERROR_LEN).collect();
+            tracing::warn!(
+                delivery_id = delivery.id,
+                url = %sub.url,
+                attempts,
+                error = %e,
+                "Webhook delivery failed"
+            );
+            if attempts >= cfg.max_attempts {
+                active.status = Set(FAILED.to_string());
+                active.next_attempt_at = Set(None);
+            } else {
+                active.next_attempt_at = Set(Some(Utc::now().naive_utc() + backoff(attempts, cfg)));
+            }
+            active.last_error = Set(Some(e));
+        }
+    }
+    active.update(db).await?;
+    Ok(())
+}
+
+/// Tandai banner published yang sudah lewat end_date dan antrekan `banner.expired`.
+pub async fn expire_banners(db: &DatabaseConnection) -> Result<usize, DbErr> {
+    let now = Utc::now().naive_utc();
+    let expired = banner::Entity::find()
+        .filter(banner::Column::Status.eq(BannerStatus::Published.as_str()))
+        .filter(banner::Column::IsActive.eq(true))
+        .filter(banner::Column::DeletedAt.is_null())
+        .filter(banner::Column::EndDate.lte(now))
+        .filter(banner::Column::ExpiryNotifiedAt.is_null())
+        .all(db)
+        .await?;
+
+    let mut notified = 0;
+    for b in expired {
+        let txn = db.begin().await?;
+        // Syarat IS NULL mencegah event ganda kalau ada instance lain yang jalan bersamaan
+        let res = banner::Entity::update_many()
+            .col_expr(banner::Column::ExpiryNotifiedAt, Expr::value(now))
+            .filter(banner::Column::Id.eq(b.id))
+            .filter(banner::Column::ExpiryNotifiedAt.is_null())
+            .exec(&txn)
+            .await?;
+        if res.rows_affected == 1 {
+            enqueue(&txn, BANNER_EXPIRED, banner_data(&b)).await?;
+            notified += 1;
+        }
+        txn.commit().await?;
+    }
+    Ok(notified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_matches_known_vector() {
+        assert_eq!(
+            sign("whsec_test", 1_700_000_000, r#"{"event":"webhook.ping"}"#),
+            "sha256=f1b0032e7f2eb55946822f997ea28bcc9b2130583c2322179bc8afecdc1c5726"
+        );
+    }
+
+    #[test]
+    fn sign_covers_timestamp_and_body() {
+        let signature = sign("whsec_test", 1_700_000_000, "{}");
+        assert_ne!(signature, sign("whsec_test", 1_700_000_001, "{}"));
+        assert_ne!(signature, sign("whsec_test", 1_700_000_000, "{ }"));
+        assert_ne!(signature, sign("whsec_other", 1_700_000_000, "{}"));
+    }
+
+    #[test]
+    fn backoff_doubles_until_max() {
+        let cfg = WebhookConfig::default();
+        let secs: Vec<i64> = (1..=9).map(|n| backoff(n, &cfg).num_seconds()).collect();
+        assert_eq!(secs, [30, 60, 120, 240, 480, 960, 1920, 3600, 3600]);
+        assert_eq!(backoff(0, &cfg).num_seconds(), 30);
+        assert_eq!(backoff(i32::MAX, &cfg).num_seconds(), 3600);
+    }
+}
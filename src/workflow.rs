@@ -0,0 +1,94 @@
+use std::fmt;
+
+/// Status banner. Hanya `Published` yang pernah disajikan ke client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BannerStatus {
+    Draft,
+    PendingReview,
+    Approved,
+    Published,
+    Archived,
+}
+
+impl BannerStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BannerStatus::Draft => "draft",
+            BannerStatus::PendingReview => "pending_review",
+            BannerStatus::Approved => "approved",
+            BannerStatus::Published => "published",
+            BannerStatus::Archived => "archived",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "draft" => Some(BannerStatus::Draft),
+            "pending_review" => Some(BannerStatus::PendingReview),
+            "approved" => Some(BannerStatus::Approved),
+            "published" => Some(BannerStatus::Published),
+            "archived" => Some(BannerStatus::Archived),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BannerStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transition {
+    Submit,
+    Approve,
+    Reject,
+    Publish,
+    Archive,
+    Reopen,
+}
+
+impl Transition {
+    pub fn verb(&self) -> &'static str {
+        match self {
+            Transition::Submit => "submit",
+            Transition::Approve => "approve",
+            Transition::Reject => "reject",
+            Transition::Publish => "publish",
+            Transition::Archive => "archive",
+            Transition::Reopen => "reopen",
+        }
+    }
+
+    // Nama aksi yang dicatat di banner_revision
+    pub fn revision_action(&self) -> &'static str {
+        match self {
+            Transition::Submit => "submitted",
+            Transition::Approve => "approved",
+            Transition::Reject => "rejected",
+            Transition::Publish => "published",
+            Transition::Archive => "archived",
+            Transition::Reopen => "reopened",
+        }
+    }
+
+    /// Status tujuan dari `from`, atau `None` kalau transisi tidak diizinkan.
+    pub fn target(&self, from: BannerStatus) -> Option<BannerStatus> {
+        use BannerStatus::*;
+        match (self, from) {
+            (Transition::Submit, Draft) => Some(PendingReview),
+            (Transition::Approve, PendingReview) => Some(Approved),
+            (Transition::Reject, PendingReview) => Some(Draft),
+            (Transition::Publish, Approved) => Some(Published),
+            (Transition::Archive, Draft | Approved | Published) => Some(Archived),
+            (Transition::Reopen, Archived) => Some(Draft),
+            _ => None,
+        }
+    }
+
+    // Submit dan reopen boleh siapa saja yang login, sisanya khusus reviewer/admin
+    pub fn requires_reviewer(&self) -> bool {
+        !matches!(self, Transition::Submit | Transition::Reopen)
+    }
+}